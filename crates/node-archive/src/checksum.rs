@@ -0,0 +1,47 @@
+//! Provides types and functions for computing the SHA-256 checksum of a
+//! Node archive, in the format published in a Node release's `SHASUMS256.txt`.
+
+use std::io::{self, Read, Seek, SeekFrom};
+
+use hex;
+use sha2::{Digest, Sha256};
+
+/// A reader that computes the SHA-256 checksum of its data as it is read.
+pub struct ChecksumRead<R: Read> {
+    source: R,
+    hasher: Sha256,
+}
+
+impl<R: Read> ChecksumRead<R> {
+    /// Constructs a new checksum reader with the specified underlying reader.
+    pub fn new(source: R) -> ChecksumRead<R> {
+        ChecksumRead {
+            source,
+            hasher: Sha256::default(),
+        }
+    }
+
+    /// Reads the remainder of the underlying reader and produces the
+    /// hex-encoded checksum of all the data it contained.
+    pub fn finish(mut self) -> io::Result<String> {
+        io::copy(&mut self, &mut io::sink())?;
+        Ok(hex::encode(self.hasher.result()))
+    }
+}
+
+impl<R: Read> Read for ChecksumRead<R> {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        let len = self.source.read(buf)?;
+        self.hasher.input(&buf[..len]);
+        Ok(len)
+    }
+}
+
+/// Computes the hex-encoded SHA-256 checksum of a seekable data source,
+/// leaving the source positioned back at its start.
+pub fn checksum<S: Read + Seek>(source: &mut S) -> io::Result<String> {
+    source.seek(SeekFrom::Start(0))?;
+    let digest = ChecksumRead::new(&mut *source).finish()?;
+    source.seek(SeekFrom::Start(0))?;
+    Ok(digest)
+}
@@ -0,0 +1,242 @@
+//! Provides the `Download` type, which streams a remote archive while caching
+//! it to disk, resuming the download if the connection is interrupted.
+
+use std::cmp::min;
+use std::fs::{rename, File, OpenOptions};
+use std::io::{self, Read, Write};
+use std::path::{Path, PathBuf};
+use std::thread;
+use std::time::Duration;
+
+use failure::{self, Fail};
+use reqwest::header::{ByteRangeSpec, ContentLength, Range};
+use reqwest::{self, Response, StatusCode};
+
+/// The maximum number of times a failed request is retried.
+const MAX_RETRIES: u32 = 5;
+
+#[derive(Fail, Debug)]
+#[fail(display = "HTTP header '{}' not found", header)]
+struct MissingHeaderError {
+    header: String
+}
+
+/// Determines the length of an HTTP response's content in bytes, using
+/// the HTTP `"Content-Length"` header.
+pub(crate) fn content_length(response: &Response) -> Result<u64, failure::Error> {
+    Ok(match response.headers().get::<ContentLength>() {
+        Some(content_length) => **content_length,
+        None => {
+            return Err(MissingHeaderError { header: String::from("Content-Length") }.into());
+        }
+    })
+}
+
+#[derive(Fail, Debug)]
+#[fail(display = "HTTP server did not resume the download (status {})", code)]
+struct ResumeRejectedError {
+    code: StatusCode
+}
+
+/// An in-progress download. The data is written to a `.partial` file next to
+/// the cache file as it is read, and the partial file is only renamed to the
+/// cache file once the full length of the content has been received, so a
+/// truncated download is never mistaken for a cached archive.
+pub struct Download {
+    url: String,
+    response: Response,
+    /// The partial file, until the download is complete.
+    file: Option<File>,
+    partial_file: PathBuf,
+    cache_file: PathBuf,
+    /// The total length of the content.
+    len: u64,
+    /// The number of bytes written to the partial file.
+    downloaded: u64,
+    /// The number of bytes produced by this reader so far.
+    position: u64,
+    /// The number of times the connection has been retried since data was last received.
+    retries: u32,
+}
+
+impl Download {
+    /// Starts downloading the specified URL to the specified cache file. If
+    /// an earlier download of the same file was interrupted, this resumes it.
+    pub fn start(url: &str, cache_file: &Path) -> Result<Self, failure::Error> {
+        let partial_file = partial_path(cache_file);
+        let file = OpenOptions::new().read(true).write(true).create(true).open(&partial_file)?;
+        let mut downloaded = file.metadata()?.len();
+
+        let mut response = request(url, downloaded)?;
+
+        // Start from scratch if the server can't resume the earlier download.
+        if downloaded > 0 && response.status() != StatusCode::PartialContent {
+            file.set_len(0)?;
+            downloaded = 0;
+            response = request(url, 0)?;
+        }
+
+        if !response.status().is_success() {
+            Err(super::HttpError { code: response.status() })?;
+        }
+
+        let len = downloaded + content_length(&response)?;
+
+        Ok(Download {
+            url: url.to_string(),
+            response,
+            file: Some(file),
+            partial_file,
+            cache_file: cache_file.to_path_buf(),
+            len,
+            downloaded,
+            position: 0,
+            retries: 0,
+        })
+    }
+
+    /// Produces the total length of the content in bytes.
+    pub fn len(&self) -> u64 {
+        self.len
+    }
+
+    /// Reads data from the network, reconnecting if the connection fails.
+    fn read_remote(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        loop {
+            let error = match self.response.read(buf) {
+                Ok(0) => io::Error::new(
+                    io::ErrorKind::UnexpectedEof,
+                    "connection closed before the download was complete",
+                ),
+                Ok(read) => {
+                    self.retries = 0;
+                    return Ok(read);
+                }
+                Err(error) => error,
+            };
+
+            self.reconnect(error)?;
+        }
+    }
+
+    /// Reconnects to the server, resuming the download from the end of the
+    /// partial file. Produces the last error if the retries run out. The
+    /// retries are counted across reconnections, so a stalled download
+    /// gives up after `MAX_RETRIES` attempts in all.
+    fn reconnect(&mut self, mut error: io::Error) -> io::Result<()> {
+        while self.retries < MAX_RETRIES {
+            self.retries += 1;
+            thread::sleep(backoff(self.retries));
+
+            match send(&self.url, self.downloaded) {
+                Ok(ref response) if response.status().is_server_error() => {
+                    let failure = super::HttpError { code: response.status() };
+                    error = io::Error::new(io::ErrorKind::Other, failure.compat());
+                }
+                Ok(response) => {
+                    // Nothing is requested by range until data has been received,
+                    // so until then the server sends the whole content.
+                    let expected = if self.downloaded > 0 {
+                        StatusCode::PartialContent
+                    } else {
+                        StatusCode::Ok
+                    };
+
+                    if response.status() != expected {
+                        let rejected = ResumeRejectedError { code: response.status() };
+                        return Err(io::Error::new(io::ErrorKind::Other, rejected.compat()));
+                    }
+
+                    self.response = response;
+                    return Ok(());
+                }
+                Err(err) => {
+                    error = io::Error::new(io::ErrorKind::Other, err.compat());
+                }
+            }
+        }
+
+        Err(error)
+    }
+
+    /// Flushes the completed partial file and renames it to the cache file.
+    fn promote(&mut self) -> io::Result<()> {
+        if let Some(file) = self.file.take() {
+            file.sync_all()?;
+            drop(file);
+            rename(&self.partial_file, &self.cache_file)?;
+        }
+        Ok(())
+    }
+}
+
+impl Read for Download {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        // First replay the data received by an earlier, interrupted download.
+        if self.position < self.downloaded {
+            let max = min(buf.len() as u64, self.downloaded - self.position) as usize;
+            let read = self.file.as_mut().unwrap().read(&mut buf[..max])?;
+            self.position += read as u64;
+            return Ok(read);
+        }
+
+        if self.downloaded == self.len {
+            self.promote()?;
+            return Ok(0);
+        }
+
+        let read = self.read_remote(buf)?;
+        self.file.as_mut().unwrap().write_all(&buf[..read])?;
+        self.downloaded += read as u64;
+        self.position += read as u64;
+
+        if self.downloaded == self.len {
+            self.promote()?;
+        }
+
+        Ok(read)
+    }
+}
+
+/// Produces the path of the partial file for a cache file.
+fn partial_path(cache_file: &Path) -> PathBuf {
+    let mut file_name = cache_file.file_name().unwrap().to_os_string();
+    file_name.push(".partial");
+    cache_file.with_file_name(file_name)
+}
+
+/// Produces the delay before the specified retry: 1s, 2s, 4s, and so on.
+fn backoff(retry: u32) -> Duration {
+    Duration::from_secs(1 << (retry - 1))
+}
+
+/// Requests the content of a URL starting from the specified byte offset,
+/// retrying with backoff if the request fails or the server has an error.
+fn request(url: &str, from: u64) -> Result<Response, failure::Error> {
+    let mut retries = 0;
+
+    loop {
+        match send(url, from) {
+            Ok(ref response) if response.status().is_server_error() && retries < MAX_RETRIES => {}
+            Ok(response) => {
+                return Ok(response);
+            }
+            Err(_) if retries < MAX_RETRIES => {}
+            Err(err) => {
+                return Err(err.into());
+            }
+        }
+
+        retries += 1;
+        thread::sleep(backoff(retries));
+    }
+}
+
+fn send(url: &str, from: u64) -> reqwest::Result<Response> {
+    let client = reqwest::Client::new()?;
+    let mut request = client.get(url)?;
+    if from > 0 {
+        request.header(Range::Bytes(vec![ByteRangeSpec::AllFrom(from)]));
+    }
+    request.send()
+}
@@ -4,31 +4,28 @@
 //! These docs show the top-level exports of this crate as re-exported of
 //! the `tarball` module (due to limitations of rustdoc); the top-level
 //! exports are re-exported from `tarball` on Unix operating systems and
-//! from `zip` on Windows operating systems.
+//! from `zip` on Windows operating systems. The `Tarball` type is available
+//! on all operating systems, since some tools (such as Yarn) are only
+//! distributed as tarballs.
 
 #![cfg_attr(feature = "universal-docs", feature(doc_cfg))]
 
 #[macro_use]
 extern crate cfg_if;
 
-cfg_if! {
-    if #[cfg(feature = "universal-docs")] {
-        extern crate tar;
-        extern crate flate2;
+extern crate tar;
+extern crate flate2;
 
-        #[doc(cfg(unix))]
-        mod tarball;
+mod tarball;
 
+cfg_if! {
+    if #[cfg(feature = "universal-docs")] {
         extern crate zip as zip_rs;
         extern crate verbatim;
 
         #[doc(cfg(windows))]
         mod zip;
     } else if #[cfg(unix)] {
-        extern crate tar;
-        extern crate flate2;
-
-        mod tarball;
     } else if #[cfg(windows)] {
         extern crate zip as zip_rs;
         extern crate verbatim;
@@ -39,43 +36,48 @@ cfg_if! {
     }
 }
 
+extern crate hex;
 extern crate progress_read;
 extern crate reqwest;
-extern crate tee;
+extern crate sha2;
 
 extern crate failure;
 #[macro_use]
 extern crate failure_derive;
 
+mod checksum;
+mod download;
+
+pub use checksum::checksum;
+
 #[derive(Fail, Debug)]
 #[fail(display = "HTTP failure ({})", code)]
 pub(crate) struct HttpError {
     code: ::reqwest::StatusCode,
 }
 
+pub use tarball::Tarball;
+
 cfg_if! {
-    if #[cfg(unix)] {
-        pub use tarball::Tarball;
-    } else if #[cfg(windows)] {
+    if #[cfg(windows)] {
         pub use zip::Zip;
-    } else {
-        compile_error!("Unsupported OS (expected 'unix' or 'windows').");
     }
 }
 
 use std::fs::File;
-use std::path::Path;
+use std::path::{Component, Path};
 
 pub trait Archive {
     fn compressed_size(&self) -> u64;
     fn uncompressed_size(&self) -> Option<u64>;
 
-    /// Unpacks the zip archive to the specified destination folder.
+    /// Unpacks the archive to the specified destination folder, returning
+    /// the hex-encoded SHA-256 checksum of the full (compressed) archive.
     fn unpack(
         self: Box<Self>,
         dest: &Path,
         progress: &mut FnMut(&(), usize),
-    ) -> Result<(), failure::Error>;
+    ) -> Result<String, failure::Error>;
 }
 
 cfg_if! {
@@ -90,6 +92,12 @@ cfg_if! {
         pub fn fetch(url: &str, cache_file: &Path) -> Result<Box<Archive>, failure::Error> {
             unimplemented!()
         }
+
+        /// Determines the name of the top-level directory of a Node archive
+        /// on disk, leaving the file positioned back at its start.
+        pub fn root_dir(source: &mut File) -> Result<Option<String>, failure::Error> {
+            unimplemented!()
+        }
     } else if #[cfg(unix)] {
         pub fn load(source: File) -> Result<Box<Archive>, failure::Error> {
             Ok(Box::new(Tarball::load(source)?))
@@ -98,6 +106,10 @@ cfg_if! {
         pub fn fetch(url: &str, cache_file: &Path) -> Result<Box<Archive>, failure::Error> {
             Ok(Box::new(Tarball::fetch(url, cache_file)?))
         }
+
+        pub fn root_dir(source: &mut File) -> Result<Option<String>, failure::Error> {
+            tarball::root_dir(source)
+        }
     } else if #[cfg(windows)] {
         pub fn load(source: File) -> Result<Box<Archive>, failure::Error> {
             Ok(Box::new(Zip::load(source)?))
@@ -106,7 +118,19 @@ cfg_if! {
         pub fn fetch(url: &str, cache_file: &Path) -> Result<Box<Archive>, failure::Error> {
             Ok(Box::new(Zip::fetch(url, cache_file)?))
         }
+
+        pub fn root_dir(source: &mut File) -> Result<Option<String>, failure::Error> {
+            zip::root_dir(source)
+        }
     } else {
         compile_error!("Unsupported OS (expected 'unix' or 'windows').");
     }
 }
+
+/// Produces the first component of an archive entry's path.
+pub(crate) fn root_component(path: &Path) -> Option<String> {
+    match path.components().next() {
+        Some(Component::Normal(name)) => name.to_str().map(|name| name.to_string()),
+        _ => None,
+    }
+}
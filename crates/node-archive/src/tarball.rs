@@ -6,15 +6,16 @@ use std::path::Path;
 use std::fs::File;
 
 use flate2::read::GzDecoder;
-use reqwest::header::{AcceptRanges, ContentLength, Range, RangeUnit, ByteRangeSpec};
+use reqwest::header::{AcceptRanges, Range, RangeUnit, ByteRangeSpec};
 use reqwest::Response;
 use reqwest;
 use tar;
-use tee::TeeReader;
 use progress_read::ProgressRead;
 use failure;
 
 use super::Archive;
+use checksum::ChecksumRead;
+use download::{content_length, Download};
 
 /// A Node installation tarball.
 pub struct Tarball<S: Read> {
@@ -38,39 +39,15 @@ impl Tarball<File> {
 
 }
 
-#[derive(Fail, Debug)]
-#[fail(display = "HTTP header '{}' not found", header)]
-struct MissingHeaderError {
-    header: String
-}
-
-/// Determines the length of an HTTP response's content in bytes, using
-/// the HTTP `"Content-Length"` header.
-fn content_length(response: &Response) -> Result<u64, failure::Error> {
-    Ok(match response.headers().get::<ContentLength>() {
-        Some(content_length) => **content_length,
-        None => {
-            return Err(MissingHeaderError { header: String::from("Content-Length") }.into());
-        }
-    })
-}
-
-impl Tarball<TeeReader<reqwest::Response, File>> {
+impl Tarball<Download> {
 
     /// Initiate fetching of a Node tarball from the given URL, returning
-    /// a tarball that can be streamed (and that tees its data to a cache
-    /// file as it streams).
+    /// a tarball that can be streamed (and that saves its data to the cache
+    /// file as it streams, resuming if the connection is interrupted).
     pub fn fetch(url: &str, cache_file: &Path) -> Result<Self, failure::Error> {
         let uncompressed_size = fetch_uncompressed_size(url)?;
-        let response = reqwest::get(url)?;
-
-        if !response.status().is_success() {
-            Err(super::HttpError { code: response.status() })?;
-        }
-
-        let compressed_size = content_length(&response)?;
-        let file = File::create(cache_file)?;
-        let data = TeeReader::new(response, file);
+        let data = Download::start(url, cache_file)?;
+        let compressed_size = data.len();
 
         Ok(Tarball {
             uncompressed_size,
@@ -84,14 +61,35 @@ impl Tarball<TeeReader<reqwest::Response, File>> {
 impl<S: Read> Archive for Tarball<S> {
     fn compressed_size(&self) -> u64 { self.compressed_size }
     fn uncompressed_size(&self) -> Option<u64> { Some(self.uncompressed_size) }
-    fn unpack(self: Box<Self>, dest: &Path, progress: &mut FnMut(&(), usize)) -> Result<(), failure::Error> {
-        let decoded = GzDecoder::new(self.data);
+    fn unpack(self: Box<Self>, dest: &Path, progress: &mut FnMut(&(), usize)) -> Result<String, failure::Error> {
+        let decoded = GzDecoder::new(ChecksumRead::new(self.data));
         let mut tarball = tar::Archive::new(ProgressRead::new(decoded, (), progress));
         tarball.unpack(dest)?;
-        Ok(())
+
+        // The tar reader stops at the end-of-archive marker, so finish reading
+        // the stream to make sure the whole archive is hashed (and cached).
+        let data = tarball.into_inner().into_inner().into_inner();
+        Ok(data.finish()?)
     }
 }
 
+/// Determines the name of the top-level directory of a tarball on disk from
+/// its first entry, leaving the file positioned back at its start.
+pub(crate) fn root_dir(source: &mut File) -> Result<Option<String>, failure::Error> {
+    source.seek(SeekFrom::Start(0))?;
+    let root = {
+        let mut tarball = tar::Archive::new(GzDecoder::new(&mut *source));
+        let mut entries = tarball.entries()?;
+        let root = match entries.next() {
+            Some(entry) => super::root_component(&entry?.path()?),
+            None => None,
+        };
+        root
+    };
+    source.seek(SeekFrom::Start(0))?;
+    Ok(root)
+}
+
 /// Fetches just the headers of a URL.
 fn headers_only(url: &str) -> Result<Response, failure::Error> {
     let client = reqwest::Client::new()?;
@@ -1,11 +1,10 @@
 //! Provides types and functions for fetching and unpacking a Node installation
 //! zip file in Windows operating systems.
 
-use std::io::{self, Read, Seek, copy};
+use std::io::{self, Read, Seek, SeekFrom, copy, sink};
 use std::path::Path;
 use std::fs::{File, create_dir_all};
 
-use reqwest;
 use progress_read::ProgressRead;
 use zip_rs::ZipArchive;
 use verbatim::PathExt;
@@ -13,6 +12,8 @@ use verbatim::PathExt;
 use failure;
 
 use super::Archive;
+use checksum::checksum;
+use download::Download;
 
 pub struct Zip<S: Read + Seek> {
     compressed_size: u64,
@@ -34,18 +35,12 @@ impl Zip<File> {
     /// Initiate fetching of a Node zip archive from the given URL, returning
     /// a `Remote` data source.
     pub fn fetch(url: &str, cache_file: &Path) -> Result<Self, failure::Error> {
-        let mut response = reqwest::get(url)?;
+        // Zip archives can't be streamed, so finish the download (which
+        // saves it to the cache file) before opening it.
+        let mut download = Download::start(url, cache_file)?;
+        copy(&mut download, &mut sink())?;
 
-        if !response.status().is_success() {
-            Err(super::HttpError { code: response.status() })?;
-        }
-
-        {
-            let mut file = File::create(cache_file)?;
-            copy(&mut response, &mut file)?;
-        }
-
-        let file = File::create(cache_file)?;
+        let file = File::open(cache_file)?;
         let compressed_size = file.metadata()?.len();
 
         Ok(Zip {
@@ -59,11 +54,15 @@ impl Zip<File> {
 impl<S: Read + Seek> Archive for Zip<S> {
     fn compressed_size(&self) -> u64 { self.compressed_size }
     fn uncompressed_size(&self) -> Option<u64> { None }
-    fn unpack(self: Box<Self>, dest: &Path, progress: &mut FnMut(&(), usize)) -> Result<(), failure::Error> {
+    fn unpack(self: Box<Self>, dest: &Path, progress: &mut FnMut(&(), usize)) -> Result<String, failure::Error> {
         // Use a verbatim path to avoid the legacy Windows 260 byte path limit.
         let dest: &Path = &dest.to_verbatim();
 
-        let mut zip = ZipArchive::new(ProgressRead::new(self.data, (), progress))?;
+        // Zip archives are read with random access, so hash the data up front.
+        let mut data = self.data;
+        let digest = checksum(&mut data)?;
+
+        let mut zip = ZipArchive::new(ProgressRead::new(data, (), progress))?;
         for i in 0..zip.len() {
             let mut entry = zip.by_index(i)?;
 
@@ -86,7 +85,24 @@ impl<S: Read + Seek> Archive for Zip<S> {
                 copy(&mut entry, &mut file)?;
             }
         }
-        Ok(())
+        Ok(digest)
     }
 
 }
+
+/// Determines the name of the top-level directory of a zip archive on disk
+/// from its first entry, leaving the file positioned back at its start.
+pub(crate) fn root_dir(source: &mut File) -> Result<Option<String>, failure::Error> {
+    source.seek(SeekFrom::Start(0))?;
+    let root = {
+        let mut zip = ZipArchive::new(&mut *source)?;
+        if zip.len() == 0 {
+            None
+        } else {
+            let entry = zip.by_index(0)?;
+            super::root_component(Path::new(entry.name()))
+        }
+    };
+    source.seek(SeekFrom::Start(0))?;
+    Ok(root)
+}
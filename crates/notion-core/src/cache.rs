@@ -0,0 +1,218 @@
+//! Provides types for inspecting and cleaning Notion's cache of downloaded
+//! tool archives.
+
+use std::fmt::{self, Display, Formatter};
+use std::fs::{read_dir, read_to_string, remove_file, File};
+use std::path::{Path, PathBuf};
+use std::str::FromStr;
+
+use catalog::Catalog;
+use installer::node::find_checksum;
+use lock::FileLock;
+use node_archive;
+use notion_fail::{Fallible, ResultExt};
+use path;
+use semver::Version;
+
+/// The suffix of an archive whose download was interrupted.
+const PARTIAL_SUFFIX: &'static str = ".partial";
+
+/// A tool whose archives are cached.
+#[derive(Eq, PartialEq, Ord, PartialOrd, Clone, Copy, Debug)]
+pub enum Tool {
+    Node,
+    Yarn,
+}
+
+impl Display for Tool {
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        f.write_str(match *self {
+            Tool::Node => "node",
+            Tool::Yarn => "yarn",
+        })
+    }
+}
+
+/// A downloaded archive in the cache.
+pub struct Archive {
+    pub tool: Tool,
+    pub version: Version,
+    pub path: PathBuf,
+    /// Indicates that the archive is the partial file of an interrupted download.
+    pub partial: bool,
+    /// The size of the archive in bytes.
+    pub size: u64,
+}
+
+/// The result of verifying a cached archive.
+pub enum Verified {
+    /// The archive matches its published checksum.
+    Ok,
+    /// The archive does not match its published checksum.
+    Mismatch { expected: String, actual: String },
+    /// The archive's download was interrupted, so it can't be verified.
+    Incomplete,
+    /// No published checksum for the archive is cached.
+    NoChecksum,
+}
+
+/// Produces all the archives in the cache, sorted by tool and version.
+pub fn archives() -> Fallible<Vec<Archive>> {
+    let mut archives = Vec::new();
+    scan(&path::node_cache_dir()?, Tool::Node, &mut archives)?;
+    scan(&path::yarn_cache_dir()?, Tool::Yarn, &mut archives)?;
+    archives.sort_by(|a, b| (a.tool, &a.version, a.partial).cmp(&(b.tool, &b.version, b.partial)));
+    Ok(archives)
+}
+
+fn scan(dir: &Path, tool: Tool, archives: &mut Vec<Archive>) -> Fallible<()> {
+    if !dir.is_dir() {
+        return Ok(());
+    }
+
+    for entry in read_dir(dir).unknown()? {
+        let entry = entry.unknown()?;
+        if !entry.file_type().unknown()?.is_file() {
+            continue;
+        }
+
+        let file_name = entry.file_name().to_string_lossy().into_owned();
+        let (name, partial) = if file_name.ends_with(PARTIAL_SUFFIX) {
+            (&file_name[..file_name.len() - PARTIAL_SUFFIX.len()], true)
+        } else {
+            (&file_name[..], false)
+        };
+
+        if let Some(version) = parse_archive_file(tool, name) {
+            archives.push(Archive {
+                tool,
+                version,
+                path: entry.path(),
+                partial,
+                size: entry.metadata().unknown()?.len(),
+            });
+        }
+    }
+
+    Ok(())
+}
+
+/// Determines the version of a tool's archive from its file name, or `None`
+/// if the file is not an archive for this platform.
+fn parse_archive_file(tool: Tool, name: &str) -> Option<Version> {
+    let (prefix, suffix) = match tool {
+        Tool::Node => (
+            "node-v".to_string(),
+            format!("-{}-{}.{}", path::OS, path::ARCH, path::archive_extension()),
+        ),
+        Tool::Yarn => ("yarn-v".to_string(), ".tar.gz".to_string()),
+    };
+
+    if !name.starts_with(&prefix) || !name.ends_with(&suffix)
+        || name.len() < prefix.len() + suffix.len()
+    {
+        return None;
+    }
+
+    Version::from_str(&name[prefix.len()..name.len() - suffix.len()]).ok()
+}
+
+impl Archive {
+    /// Determines whether the archive's version is in the catalog.
+    pub fn is_used(&self, catalog: &Catalog) -> bool {
+        match self.tool {
+            Tool::Node => catalog.node.contains(&self.version),
+            Tool::Yarn => catalog.yarn.contains(&self.version),
+        }
+    }
+
+    /// Checks the archive against the checksum published for its version,
+    /// which is cached alongside Node archives when they are downloaded. The
+    /// cached archives are shared by every mirror, so the checksums cached
+    /// from any mirror will do.
+    pub fn verify(&self) -> Fallible<Verified> {
+        if self.partial {
+            return Ok(Verified::Incomplete);
+        }
+
+        let archive_file = path::archive_file(&self.version.to_string());
+        let mut expected = None;
+        for shasums_file in self.shasums_files()? {
+            let shasums = read_to_string(&shasums_file).unknown()?;
+            expected = find_checksum(&shasums, &archive_file);
+            if expected.is_some() {
+                break;
+            }
+        }
+
+        let expected = match expected {
+            Some(expected) => expected,
+            None => {
+                return Ok(Verified::NoChecksum);
+            }
+        };
+
+        let actual = node_archive::checksum(&mut File::open(&self.path).unknown()?).unknown()?;
+        if expected.eq_ignore_ascii_case(&actual) {
+            Ok(Verified::Ok)
+        } else {
+            Ok(Verified::Mismatch { expected, actual })
+        }
+    }
+
+    /// Removes the archive from the cache. The cached checksums for its
+    /// version are removed too, unless another archive of the same version
+    /// (a complete download alongside a partial one) is still in the cache.
+    /// If another process is installing the archive's version (and so may
+    /// still be downloading or unpacking it), the archive is left alone and
+    /// this produces `false`.
+    pub fn remove(&self) -> Fallible<bool> {
+        let version = self.version.to_string();
+        let lock_file = match self.tool {
+            Tool::Node => path::node_install_lock_file(&version)?,
+            Tool::Yarn => path::yarn_install_lock_file(&version)?,
+        };
+        let _lock = match FileLock::try_acquire(&lock_file)? {
+            Some(lock) => lock,
+            None => {
+                return Ok(false);
+            }
+        };
+
+        remove_file(&self.path).unknown()?;
+
+        let archive_file = self.path.with_file_name(path::archive_file(&self.version.to_string()));
+        let mut partial_file = archive_file.clone().into_os_string();
+        partial_file.push(PARTIAL_SUFFIX);
+
+        if !archive_file.is_file() && !Path::new(&partial_file).is_file() {
+            for shasums_file in self.shasums_files()? {
+                remove_file(&shasums_file).unknown()?;
+            }
+        }
+
+        Ok(true)
+    }
+
+    /// Produces the paths of the `SHASUMS256.txt` files cached from every
+    /// mirror for the archive's version.
+    fn shasums_files(&self) -> Fallible<Vec<PathBuf>> {
+        let mut files = Vec::new();
+        if self.tool != Tool::Node {
+            return Ok(files);
+        }
+
+        let prefix = path::shasums_file_prefix(&self.version.to_string());
+        for entry in read_dir(path::node_cache_dir()?).unknown()? {
+            let entry = entry.unknown()?;
+            let file_name = entry.file_name().to_string_lossy().into_owned();
+            if file_name.starts_with(&prefix) && file_name.ends_with(".txt")
+                && entry.file_type().unknown()?.is_file()
+            {
+                files.push(entry.path());
+            }
+        }
+
+        Ok(files)
+    }
+}
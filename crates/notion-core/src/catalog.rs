@@ -1,10 +1,10 @@
 //! Provides types for working with Notion's local _catalog_, the local repository
 //! of available tool versions.
 
-use std::collections::{BTreeMap, BTreeSet, HashSet};
+use std::collections::{BTreeMap, BTreeSet, HashMap, HashSet};
 use std::fs::{self, remove_dir_all, File};
 use std::io::{self, ErrorKind, Write};
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
 use std::str::FromStr;
 use std::string::ToString;
 use std::time::{Duration, SystemTime};
@@ -12,7 +12,9 @@ use std::time::{Duration, SystemTime};
 use lazycell::LazyCell;
 use readext::ReadExt;
 use reqwest;
-use reqwest::header::{CacheControl, CacheDirective, Expires, HttpDate};
+use reqwest::header::{CacheControl, CacheDirective, ETag, EntityTag, Expires, HttpDate,
+                      IfModifiedSince, IfNoneMatch, LastModified};
+use reqwest::StatusCode;
 use serde_json;
 use tempfile::NamedTempFile;
 use toml;
@@ -20,15 +22,22 @@ use toml;
 use config::{Config, NodeConfig};
 use installer::Installed;
 use installer::node::Installer;
+use installer::yarn::Installer as YarnInstaller;
+use lock::FileLock;
 use notion_fail::{Fallible, NotionError, NotionFail, ResultExt};
 use path::{self, user_catalog_file};
-use semver::{Version, VersionReq};
+use semver::Version;
 use serial;
 use serial::touch;
-use style::progress_spinner;
+use style::{display_warning, progress_spinner};
+use version::VersionSpec;
 
-/// URL of the index of available Node versions on the public Node server.
-const PUBLIC_NODE_VERSION_INDEX: &'static str = "https://nodejs.org/dist/index.json";
+/// URL of the npm registry metadata for Yarn, which serves as the index of available Yarn versions.
+const PUBLIC_YARN_VERSION_INDEX: &'static str = "https://registry.npmjs.org/yarn";
+
+/// The age (in seconds) within which a Node version counts as recently used,
+/// and so is never pruned.
+const RECENT_USE_AGE: u64 = 30 * 24 * 60 * 60;
 
 /// Lazily loaded tool catalog.
 pub struct LazyCatalog {
@@ -57,6 +66,7 @@ impl LazyCatalog {
 /// The catalog of tool versions available locally.
 pub struct Catalog {
     pub node: NodeCatalog,
+    pub yarn: YarnCatalog,
 }
 
 /// The catalog of Node versions available locally.
@@ -68,12 +78,25 @@ pub struct NodeCatalog {
     pub versions: BTreeSet<Version>,
 }
 
+/// The catalog of Yarn versions available locally.
+pub struct YarnCatalog {
+    /// The currently activated Yarn version, if any.
+    pub activated: Option<Version>,
+
+    // A sorted collection of the available versions in the catalog.
+    pub versions: BTreeSet<Version>,
+}
+
 impl Catalog {
-    /// Returns the current tool catalog.
+    /// Returns the current tool catalog. Versions whose installation directory
+    /// is missing (for example, because it was deleted by hand) are left out.
     fn current() -> Fallible<Catalog> {
         let path = user_catalog_file()?;
         let src = touch(&path)?.read_into_string().unknown()?;
-        src.parse()
+        let mut catalog: Catalog = src.parse()?;
+        catalog.node.versions = installed_only(catalog.node.versions, path::node_version_dir)?;
+        catalog.yarn.versions = installed_only(catalog.yarn.versions, path::yarn_version_dir)?;
+        Ok(catalog)
     }
 
     /// Returns a pretty-printed TOML representation of the contents of the catalog.
@@ -81,35 +104,125 @@ impl Catalog {
         toml::to_string_pretty(&self.to_serial()).unwrap()
     }
 
-    /// Saves the contents of the catalog to the user's catalog file.
-    pub fn save(&self) -> Fallible<()> {
+    /// Saves the contents of the catalog to the user's catalog file. The file is
+    /// replaced atomically, so other processes never see a partially written catalog.
+    /// This must only be called while holding the catalog lock (see `update`).
+    fn save(&self) -> Fallible<()> {
         let path = user_catalog_file()?;
-        let mut file = File::create(&path).unknown()?;
+        let mut file = NamedTempFile::new_in(path.parent().unwrap()).unknown()?;
         file.write_all(self.to_string().as_bytes()).unknown()?;
+        file.as_file().sync_all().unknown()?;
+        file.persist(&path).unknown()?;
         Ok(())
     }
 
-    /// Activates a Node version matching the specified semantic versioning requirements.
-    pub fn activate_node(&mut self, matching: &VersionReq, config: &Config) -> Fallible<()> {
+    /// Applies a change to the catalog and saves it. The change is made to a
+    /// fresh copy of the catalog while holding the catalog lock, so that changes
+    /// saved concurrently by other Notion processes are not lost.
+    fn update<F>(&mut self, change: F) -> Fallible<()>
+    where
+        F: FnOnce(&mut Catalog),
+    {
+        let _lock = FileLock::acquire(&path::user_catalog_lock_file()?)?;
+        *self = Catalog::current()?;
+        change(self);
+        self.save()
+    }
+
+    /// Activates a Node version matching the specified version specification.
+    pub fn activate_node(&mut self, matching: &VersionSpec, config: &Config) -> Fallible<()> {
         let installed = self.install_node(matching, config)?;
         let version = Some(installed.into_version());
 
         if self.node.activated != version {
-            self.node.activated = version;
-            self.save()?;
+            self.update(|catalog| {
+                catalog.node.activated = version;
+            })?;
         }
 
         Ok(())
     }
 
-    /// Installs a Node version matching the specified semantic versioning requirements.
-    pub fn install_node(&mut self, matching: &VersionReq, config: &Config) -> Fallible<Installed> {
+    /// Installs a Node version matching the specified version specification.
+    /// If another process is installing the same version, this waits for it
+    /// to finish and then reuses its installation.
+    pub fn install_node(&mut self, matching: &VersionSpec, config: &Config) -> Fallible<Installed> {
+        // Offline, settle for any installed version that matches.
+        if config.offline() {
+            if let Some(version) = self.node.resolve_local(matching, config) {
+                return Ok(Installed::Already(version));
+            }
+        }
+
         let installer = self.node.resolve_remote(&matching, config)?;
-        let installed = installer.install(&self.node).unknown()?;
+        self.install_node_with(installer)
+    }
 
-        if let &Installed::Now(ref version) = &installed {
-            self.node.versions.insert(version.clone());
-            self.save()?;
+    /// Installs Node from an archive file, such as a vendored toolchain. The
+    /// version is determined from the archive unless it is specified.
+    pub fn install_node_from_file(
+        &mut self,
+        archive: &Path,
+        version: Option<Version>,
+        config: &Config,
+    ) -> Fallible<Installed> {
+        let installer = Installer::local(archive, version, &config.node_mirror())?;
+        self.install_node_with(installer)
+    }
+
+    fn install_node_with(&mut self, installer: Installer) -> Fallible<Installed> {
+        let installed = installer.install(&self.node)?;
+        let version = installed.version().clone();
+
+        // A fresh install counts as a use, so it isn't pruned before it's ever launched.
+        if let Installed::Now(_) = installed {
+            record_node_use(&version)?;
+        }
+
+        if !self.node.contains(&version) {
+            self.update(|catalog| {
+                catalog.node.versions.insert(version);
+            })?;
+        }
+
+        Ok(installed)
+    }
+
+    /// Activates a Yarn version matching the specified version specification.
+    pub fn activate_yarn(&mut self, matching: &VersionSpec, config: &Config) -> Fallible<()> {
+        let installed = self.install_yarn(matching, config)?;
+        let version = Some(installed.into_version());
+
+        if self.yarn.activated != version {
+            self.update(|catalog| {
+                catalog.yarn.activated = version;
+            })?;
+        }
+
+        Ok(())
+    }
+
+    /// Installs a Yarn version matching the specified version specification.
+    pub fn install_yarn(&mut self, matching: &VersionSpec, config: &Config) -> Fallible<Installed> {
+        // Resolving Yarn versions always requires the npm registry, so offline
+        // only an installed version that matches will do.
+        if config.offline() {
+            if let Some(version) = self.yarn.resolve_local(matching) {
+                return Ok(Installed::Already(version));
+            }
+            throw!(OfflineError {
+                what: format!("Yarn {}", matching),
+            });
+        }
+
+        let installer = self.yarn.resolve_public(&matching)?;
+        let installed = installer.install(&self.yarn)?;
+        let version = installed.version().clone();
+
+        if !self.yarn.contains(&version) {
+            self.update(|catalog| {
+                catalog.yarn.versions.insert(version);
+            })?;
         }
 
         Ok(installed)
@@ -127,11 +240,18 @@ impl Catalog {
                 )).unknown()?;
             }
 
+            let _lock = FileLock::acquire(&path::node_install_lock_file(&version.to_string())?)?;
+
             remove_dir_all(home).unknown()?;
 
-            self.node.versions.remove(version);
+            let usage_file = path::node_usage_file(&version.to_string())?;
+            if usage_file.is_file() {
+                fs::remove_file(usage_file).unknown()?;
+            }
 
-            self.save()?;
+            self.update(|catalog| {
+                catalog.node.versions.remove(version);
+            })?;
         }
 
         Ok(())
@@ -142,7 +262,7 @@ impl Catalog {
 #[derive(Fail, Debug)]
 #[fail(display = "No Node version found for {}", matching)]
 struct NoNodeVersionFoundError {
-    matching: VersionReq,
+    matching: VersionSpec,
 }
 impl NotionFail for NoNodeVersionFoundError {
     fn is_user_friendly(&self) -> bool {
@@ -153,6 +273,99 @@ impl NotionFail for NoNodeVersionFoundError {
     }
 }
 
+/// Thrown when there are Node versions matching a requested semver specifier,
+/// but none of them are available for the current platform.
+#[derive(Fail, Debug)]
+#[fail(display = "No Node version found for {} on {} (v{} matches, but is not available for this platform)",
+       matching, platform, latest)]
+struct NoNodeVersionForPlatformError {
+    matching: VersionSpec,
+    platform: String,
+    latest: Version,
+}
+impl NotionFail for NoNodeVersionForPlatformError {
+    fn is_user_friendly(&self) -> bool {
+        true
+    }
+    fn exit_code(&self) -> i32 {
+        100
+    }
+}
+
+/// Thrown when there is no Yarn version matching a requested version specifier.
+#[derive(Fail, Debug)]
+#[fail(display = "No Yarn version found for {}", matching)]
+struct NoYarnVersionFoundError {
+    matching: VersionSpec,
+}
+impl NotionFail for NoYarnVersionFoundError {
+    fn is_user_friendly(&self) -> bool {
+        true
+    }
+    fn exit_code(&self) -> i32 {
+        100
+    }
+}
+
+/// Thrown when something has to be downloaded but Notion is offline.
+#[derive(Fail, Debug)]
+#[fail(display = "Notion is offline, so {} cannot be downloaded", what)]
+pub(crate) struct OfflineError {
+    pub(crate) what: String,
+}
+impl NotionFail for OfflineError {
+    fn is_user_friendly(&self) -> bool {
+        true
+    }
+    fn exit_code(&self) -> i32 {
+        10
+    }
+}
+
+/// Filters a set of versions down to those with an installation directory.
+/// Installs only move a version's directory into place once it is complete,
+/// so an existing directory is a complete one.
+fn installed_only<F>(versions: BTreeSet<Version>, version_dir: F) -> Fallible<BTreeSet<Version>>
+where
+    F: Fn(&str) -> Fallible<PathBuf>,
+{
+    let mut installed = BTreeSet::new();
+    for version in versions {
+        if version_dir(&version.to_string())?.is_dir() {
+            installed.insert(version);
+        }
+    }
+    Ok(installed)
+}
+
+/// Computes the total size of the files in a directory tree, in bytes.
+fn dir_size(dir: &Path) -> io::Result<u64> {
+    let mut size = 0;
+    for entry in fs::read_dir(dir)? {
+        let entry = entry?;
+        let file_type = entry.file_type()?;
+        if file_type.is_dir() {
+            size += dir_size(&entry.path())?;
+        } else if file_type.is_file() {
+            size += entry.metadata()?.len();
+        }
+    }
+    Ok(size)
+}
+
+/// Records that a Node version is being used now. The modification time of
+/// the version's usage file is the time of its last use.
+pub fn record_node_use(version: &Version) -> Fallible<()> {
+    let file = path::node_usage_file(&version.to_string())?;
+    fs::create_dir_all(file.parent().unwrap()).unknown()?;
+
+    let now = SystemTime::now()
+        .duration_since(SystemTime::UNIX_EPOCH)
+        .map(|since| since.as_secs())
+        .unwrap_or(0);
+    fs::write(&file, format!("{}\n", now)).unknown()
+}
+
 /// Reads a file, if it exists.
 fn read_file_opt(path: &PathBuf) -> io::Result<Option<String>> {
     let result: io::Result<String> = fs::read_to_string(path);
@@ -168,16 +381,33 @@ fn read_file_opt(path: &PathBuf) -> io::Result<Option<String>> {
     }
 }
 
-/// Reads a public index from the Node cache, if it exists and hasn't expired.
-fn read_cached_opt() -> Fallible<Option<serial::index::Index>> {
-    let expiry: Option<String> = read_file_opt(&path::node_index_expiry_file()?).unknown()?;
+/// Produces the key identifying a Node mirror's files in the Node cache, which
+/// is its scheme, host, and path with punctuation replaced by hyphens (e.g.,
+/// `https-nodejs.org-dist` for `https://nodejs.org/dist/`), so that mirrors
+/// differing only in scheme or path don't share cached files.
+pub(crate) fn mirror_key(mirror: &str) -> String {
+    let url = match mirror.find("://") {
+        Some(index) => format!("{}-{}", &mirror[..index], &mirror[index + 3..]),
+        None => mirror.to_string(),
+    };
+
+    url.trim_matches('/')
+        .chars()
+        .map(|c| if c.is_ascii_alphanumeric() || c == '.' { c } else { '-' })
+        .collect()
+}
+
+/// Reads a mirror's index from the Node cache, if it exists and hasn't expired.
+fn read_cached_opt(mirror: &str) -> Fallible<Option<serial::index::Index>> {
+    let key = mirror_key(mirror);
+    let expiry: Option<String> = read_file_opt(&path::node_index_expiry_file(&key)?).unknown()?;
 
     if let Some(string) = expiry {
         let expiry_date: HttpDate = HttpDate::from_str(&string).unknown()?;
         let current_date: HttpDate = HttpDate::from(SystemTime::now());
 
         if current_date < expiry_date {
-            let cached: Option<String> = read_file_opt(&path::node_index_file()?).unknown()?;
+            let cached: Option<String> = read_file_opt(&path::node_index_file(&key)?).unknown()?;
 
             if let Some(string) = cached {
                 return Ok(serde_json::de::from_str(&string).unknown()?);
@@ -188,6 +418,38 @@ fn read_cached_opt() -> Fallible<Option<serial::index::Index>> {
     Ok(None)
 }
 
+/// Reads a mirror's index from the Node cache, if it exists, even if it has expired.
+fn read_cached_any(mirror: &str) -> Fallible<Option<Index>> {
+    let cached: Option<String> = read_file_opt(&path::node_index_file(&mirror_key(mirror))?).unknown()?;
+
+    if let Some(string) = cached {
+        let serial: serial::index::Index = serde_json::de::from_str(&string).unknown()?;
+        return Ok(Some(serial.into_index()?));
+    }
+
+    Ok(None)
+}
+
+/// Reads a mirror's index from the Node cache without going to the network,
+/// even if it has expired (in which case this warns that it is stale).
+fn offline_index(mirror: &str) -> Fallible<Index> {
+    if let Some(serial) = read_cached_opt(mirror)? {
+        return serial.into_index();
+    }
+
+    match read_cached_any(mirror)? {
+        Some(index) => {
+            display_warning(&"Notion is offline, so it is using a stale copy of the Node index");
+            Ok(index)
+        }
+        None => {
+            throw!(OfflineError {
+                what: String::from("the Node index"),
+            });
+        }
+    }
+}
+
 /// Get the cache max-age of an HTTP reponse.
 fn max_age(response: &reqwest::Response) -> u32 {
     if let Some(cache_control_header) = response.headers().get::<CacheControl>() {
@@ -198,8 +460,142 @@ fn max_age(response: &reqwest::Response) -> u32 {
         }
     }
 
-    // Default to four hours.
-    4 * 60 * 60
+    // Default to fifteen minutes. Refreshing an unchanged index is cheap,
+    // since it is revalidated rather than downloaded again.
+    15 * 60
+}
+
+/// Fetches the index of the public Node server (or the configured mirror of it),
+/// or loads it from the cache if the cached copy hasn't expired.
+fn public_index(mirror: &str) -> Fallible<Index> {
+    Ok(match read_cached_opt(mirror).unknown()? {
+        Some(serial) => serial,
+        None => fetch_index(mirror)?,
+    }.into_index()?)
+}
+
+/// Fetches the index of a Node mirror and caches it. If there is a cached
+/// copy, the request is conditional on the `ETag` and `Last-Modified`
+/// validators saved with it, and if the index hasn't changed (the server
+/// responds with `304 Not Modified`), the cached copy's expiry is renewed
+/// instead of downloading the index again. A cached copy that can't be parsed
+/// is never revalidated, so that it gets replaced.
+fn fetch_index(mirror: &str) -> Fallible<serial::index::Index> {
+    let key = mirror_key(mirror);
+    let url = format!("{}index.json", mirror);
+    let cached: Option<serial::index::Index> =
+        match read_file_opt(&path::node_index_file(&key)?).unknown()? {
+            Some(string) => serde_json::de::from_str(&string).ok(),
+            None => None,
+        };
+
+    let spinner = progress_spinner(&format!("Fetching public registry: {}", url));
+    let client = reqwest::Client::new();
+    let mut request = client.get(&url);
+
+    if cached.is_some() {
+        let validators: Option<String> =
+            read_file_opt(&path::node_index_validators_file(&key)?).unknown()?;
+
+        if let Some(validators) = validators {
+            let (etag, last_modified) = parse_validators(&validators);
+            if let Some(etag) = etag {
+                request.header(IfNoneMatch::Items(vec![etag]));
+            }
+            if let Some(last_modified) = last_modified {
+                request.header(IfModifiedSince(last_modified));
+            }
+        }
+    }
+
+    let mut response: reqwest::Response = request.send().unknown()?;
+
+    let serial: serial::index::Index = match (response.status(), cached) {
+        (StatusCode::NotModified, Some(cached)) => cached,
+        _ => {
+            let response_text: String = response.text().unknown()?;
+            let serial: serial::index::Index =
+                serde_json::de::from_str(&response_text).unknown()?;
+            let cached: NamedTempFile = NamedTempFile::new().unknown()?;
+
+            // Block to borrow cached for cached_file.
+            {
+                let mut cached_file: &File = cached.as_file();
+                cached_file.write(response_text.as_bytes()).unknown()?;
+            }
+
+            cached.persist(path::node_index_file(&key)?).unknown()?;
+            save_validators(&key, &response)?;
+            serial
+        }
+    };
+
+    let expiry: NamedTempFile = NamedTempFile::new().unknown()?;
+
+    // Block to borrow expiry for expiry_file.
+    {
+        let mut expiry_file: &File = expiry.as_file();
+
+        if let Some(expires_header) = response.headers().get::<Expires>() {
+            write!(expiry_file, "{}", expires_header).unknown()?;
+        } else {
+            let expiry_date = SystemTime::now() + Duration::from_secs(max_age(&response).into());
+
+            write!(expiry_file, "{}", HttpDate::from(expiry_date)).unknown()?;
+        }
+    }
+
+    expiry.persist(path::node_index_expiry_file(&key)?).unknown()?;
+
+    spinner.finish_and_clear();
+    Ok(serial)
+}
+
+/// Saves the `ETag` and `Last-Modified` validators of an index response, one
+/// header per line, so that the next fetch of the index can be conditional.
+fn save_validators(key: &str, response: &reqwest::Response) -> Fallible<()> {
+    let mut validators = String::new();
+    if let Some(etag) = response.headers().get::<ETag>() {
+        validators.push_str(&format!("ETag: {}\n", etag));
+    }
+    if let Some(last_modified) = response.headers().get::<LastModified>() {
+        validators.push_str(&format!("Last-Modified: {}\n", last_modified));
+    }
+
+    let file = path::node_index_validators_file(key)?;
+    if validators.is_empty() {
+        if file.is_file() {
+            fs::remove_file(&file).unknown()?;
+        }
+    } else {
+        let saved: NamedTempFile = NamedTempFile::new().unknown()?;
+
+        // Block to borrow saved for saved_file.
+        {
+            let mut saved_file: &File = saved.as_file();
+            saved_file.write(validators.as_bytes()).unknown()?;
+        }
+
+        saved.persist(&file).unknown()?;
+    }
+    Ok(())
+}
+
+/// Parses the validators saved by `save_validators`. Unrecognized or
+/// malformed lines are ignored, which only makes the next fetch unconditional.
+fn parse_validators(src: &str) -> (Option<EntityTag>, Option<HttpDate>) {
+    let mut etag = None;
+    let mut last_modified = None;
+
+    for line in src.lines() {
+        if line.starts_with("ETag: ") {
+            etag = EntityTag::from_str(&line["ETag: ".len()..]).ok();
+        } else if line.starts_with("Last-Modified: ") {
+            last_modified = HttpDate::from_str(&line["Last-Modified: ".len()..]).ok();
+        }
+    }
+
+    (etag, last_modified)
 }
 
 impl NodeCatalog {
@@ -208,96 +604,306 @@ impl NodeCatalog {
         self.versions.contains(version)
     }
 
-    /// Resolves the specified semantic versioning requirements from a remote distributor.
-    fn resolve_remote(&self, matching: &VersionReq, config: &Config) -> Fallible<Installer> {
+    /// Computes the disk space used by an installed Node version, in bytes, or
+    /// `None` if its installation directory can't be read (for example,
+    /// because it was deleted by hand).
+    pub fn disk_usage(&self, version: &Version) -> Fallible<Option<u64>> {
+        Ok(dir_size(&path::node_version_dir(&version.to_string())?).ok())
+    }
+
+    /// Produces the time a Node version was last launched (or installed), if
+    /// it has been recorded.
+    pub fn last_used(&self, version: &Version) -> Fallible<Option<SystemTime>> {
+        let file = path::node_usage_file(&version.to_string())?;
+        if !file.is_file() {
+            return Ok(None);
+        }
+        Ok(Some(fs::metadata(&file).unknown()?.modified().unknown()?))
+    }
+
+    /// Produces the installed Node versions that can be pruned, oldest first.
+    /// A version is kept if it is activated, listed in `in_use`, used within
+    /// the last 30 days, or among the newest `keep` versions of its major version.
+    pub fn prunable(&self, in_use: &[Version], keep: usize) -> Fallible<Vec<Version>> {
+        let now = SystemTime::now();
+        let mut kept: HashMap<u64, usize> = HashMap::new();
+        let mut prunable = Vec::new();
+
+        // Visit the newest versions first, so that the newest of each major version are kept.
+        for version in self.versions.iter().rev() {
+            let kept = kept.entry(version.major).or_insert(0);
+            if *kept < keep {
+                *kept += 1;
+                continue;
+            }
+
+            if self.activated.as_ref() == Some(version) || in_use.contains(version) {
+                continue;
+            }
+
+            let recent = match self.last_used(version)? {
+                Some(used) => now.duration_since(used)
+                    .map(|age| age.as_secs() < RECENT_USE_AGE)
+                    .unwrap_or(true),
+                None => false,
+            };
+
+            if !recent {
+                prunable.push(version.clone());
+            }
+        }
+
+        prunable.reverse();
+        Ok(prunable)
+    }
+
+    /// Produces the index of Node versions available from a remote distributor.
+    pub fn ls_remote(&self, config: &Config) -> Fallible<Index> {
         match config.node {
             Some(NodeConfig {
-                resolve: Some(ref plugin),
+                ls_remote: Some(ref plugin),
                 ..
-            }) => plugin.resolve(matching),
-            _ => self.resolve_public(matching),
+            }) => {
+                if config.offline() {
+                    throw!(OfflineError {
+                        what: String::from("the list of Node versions"),
+                    });
+                }
+                plugin.ls_remote()
+            }
+            _ if config.offline() => offline_index(&config.node_mirror()),
+            _ => public_index(&config.node_mirror()),
         }
     }
 
-    /// Resolves the specified semantic versioning requirements from the public distributor (`https://nodejs.org`).
-    fn resolve_public(&self, matching: &VersionReq) -> Fallible<Installer> {
-        let index: Index = match read_cached_opt().unknown()? {
-            Some(serial) => serial,
-            None => {
-                let spinner = progress_spinner(&format!(
-                    "Fetching public registry: {}",
-                    PUBLIC_NODE_VERSION_INDEX
-                ));
-                let mut response: reqwest::Response = reqwest::get(PUBLIC_NODE_VERSION_INDEX).unknown()?;
-                let response_text: String = response.text().unknown()?;
-                let cached: NamedTempFile = NamedTempFile::new().unknown()?;
-
-                // Block to borrow cached for cached_file.
-                {
-                    let mut cached_file: &File = cached.as_file();
-                    cached_file.write(response_text.as_bytes()).unknown()?;
+    /// Resolves the specified version specification from a remote distributor.
+    fn resolve_remote(&self, matching: &VersionSpec, config: &Config) -> Fallible<Installer> {
+        match config.node {
+            Some(NodeConfig {
+                resolve: Some(ref plugin),
+                ..
+            }) => {
+                if config.offline() {
+                    throw!(OfflineError {
+                        what: format!("Node {}", matching),
+                    });
                 }
+                plugin.resolve(matching)
+            }
+            _ if config.offline() => self.resolve_offline(matching, &config.node_mirror()),
+            _ => self.resolve_public(matching, &config.node_mirror()),
+        }
+    }
 
-                cached.persist(path::node_index_file()?).unknown()?;
-
-                let expiry: NamedTempFile = NamedTempFile::new().unknown()?;
+    /// Resolves the specified version specification from the public distributor
+    /// (`https://nodejs.org`), or the configured mirror of it.
+    fn resolve_public(&self, matching: &VersionSpec, mirror: &str) -> Fallible<Installer> {
+        let index = public_index(mirror)?;
+        let version = latest_for_platform(&index, matching)?;
+        Installer::public(version, mirror)
+    }
 
-                // Block to borrow expiry for expiry_file.
-                {
-                    let mut expiry_file: &File = expiry.as_file();
+    /// Resolves the specified version specification without going to the
+    /// network, using the cached (possibly stale) index of the configured
+    /// mirror and the archives in the cache. This prefers the latest matching
+    /// version, but settles for an older one if only that one is cached.
+    fn resolve_offline(&self, matching: &VersionSpec, mirror: &str) -> Fallible<Installer> {
+        let index = offline_index(mirror)?;
+        let latest = latest_for_platform(&index, matching)?;
+
+        let platform = path::platform_key();
+        let cache_dir = path::node_cache_dir()?;
+        let cached = index.entries.iter()
+            .rev()
+            .filter(|&(ref k, ref v)| v.matches(k, matching) && v.files.contains(&platform))
+            .map(|(k, _)| k)
+            .filter(|k| cache_dir.join(path::archive_file(&k.to_string())).is_file())
+            .next()
+            .map(|k| k.clone());
 
-                    if let Some(expires_header) = response.headers().get::<Expires>() {
-                        write!(expiry_file, "{}", expires_header).unknown()?;
-                    } else {
-                        let expiry_date = SystemTime::now() + Duration::from_secs(max_age(&response).into());
+        match cached {
+            Some(version) => Installer::offline(version, mirror),
+            None => {
+                throw!(OfflineError {
+                    what: format!("Node v{}", latest),
+                });
+            }
+        }
+    }
 
-                        write!(expiry_file, "{}", HttpDate::from(expiry_date)).unknown()?;
-                    }
+    /// Resolves the specified version specification from the local catalog.
+    pub fn resolve_local(&self, spec: &VersionSpec, config: &Config) -> Option<Version> {
+        // The latest version is the latest one in the index, not the latest
+        // one that happens to be installed. Since this shouldn't go to the
+        // network, only use a cached index that hasn't expired (or, offline,
+        // any cached index).
+        if let &VersionSpec::Latest = spec {
+            let mirror = config.node_mirror();
+            let index = if config.offline() {
+                read_cached_any(&mirror).ok().and_then(|index| index)
+            } else {
+                match read_cached_opt(&mirror) {
+                    Ok(Some(serial)) => serial.into_index().ok(),
+                    _ => None,
                 }
+            };
 
-                expiry.persist(path::node_index_expiry_file()?).unknown()?;
-
-                let serial: serial::index::Index = serde_json::de::from_str(&response_text).unknown()?;
+            return index
+                .and_then(|index| latest_for_platform(&index, spec).ok())
+                .and_then(|latest| if self.contains(&latest) { Some(latest) } else { None });
+        }
 
-                spinner.finish_and_clear();
-                serial
-            },
-        }.into_index()?;
+        // Matching LTS releases requires the public index, but only use it if
+        // it's already cached, since this shouldn't go to the network.
+        let index = if spec.is_lts() {
+            match read_cached_any(&config.node_mirror()) {
+                Ok(Some(index)) => Some(index),
+                _ => {
+                    return None;
+                }
+            }
+        } else {
+            None
+        };
 
-        let version = index.entries.iter()
+        self.versions
+            .iter()
             .rev()
-            // ISSUE #34: also make sure this OS is available for this version
-            .skip_while(|&(ref k, _)| !matching.matches(k))
+            .skip_while(|v| {
+                let lts = index
+                    .as_ref()
+                    .and_then(|index| index.entries.get(*v))
+                    .and_then(|data| data.lts.as_ref().map(String::as_str));
+                !spec.matches(v, lts)
+            })
             .next()
-            .map(|(k, _)| k.clone());
+            .map(|v| v.clone())
+    }
+}
+
+/// Finds the latest version in an index that matches the specified version
+/// specification and is available for this platform.
+fn latest_for_platform(index: &Index, matching: &VersionSpec) -> Fallible<Version> {
+    let platform = path::platform_key();
+    let version = index.entries.iter()
+        .rev()
+        .skip_while(|&(ref k, ref v)| !v.matches(k, matching) || !v.files.contains(&platform))
+        .next()
+        .map(|(k, _)| k.clone());
+    if let Some(version) = version {
+        return Ok(version);
+    }
+
+    // Distinguish between no matching version at all and no matching version for this platform.
+    let unavailable = index.entries.iter()
+        .rev()
+        .skip_while(|&(ref k, ref v)| !v.matches(k, matching))
+        .next()
+        .map(|(k, _)| k);
+    if let Some(unavailable) = unavailable {
+        throw!(NoNodeVersionForPlatformError {
+            matching: matching.clone(),
+            platform: platform,
+            latest: unavailable.clone(),
+        });
+    } else {
+        throw!(NoNodeVersionFoundError {
+            matching: matching.clone(),
+        });
+    }
+}
+
+impl YarnCatalog {
+    /// Tests whether this Yarn catalog contains the specified Yarn version.
+    pub fn contains(&self, version: &Version) -> bool {
+        self.versions.contains(version)
+    }
+
+    /// Resolves the specified version specification from the public Yarn releases.
+    fn resolve_public(&self, matching: &VersionSpec) -> Fallible<YarnInstaller> {
+        let index = public_yarn_index()?;
+
+        // Prefer the release tagged as `latest`, which skips over prereleases.
+        let version = match (matching, index.latest) {
+            (&VersionSpec::Latest, Some(latest)) => Some(latest),
+            _ => index
+                .versions
+                .iter()
+                .rev()
+                .skip_while(|v| !matching.matches(v, None))
+                .next()
+                .map(|v| v.clone()),
+        };
+
         if let Some(version) = version {
-            Installer::public(version)
+            YarnInstaller::public(version)
         } else {
-            throw!(NoNodeVersionFoundError {
+            throw!(NoYarnVersionFoundError {
                 matching: matching.clone(),
             });
         }
     }
 
-    /// Resolves the specified semantic versioning requirements from the local catalog.
-    pub fn resolve_local(&self, req: &VersionReq) -> Option<Version> {
+    /// Resolves the specified version specification from the local catalog.
+    pub fn resolve_local(&self, spec: &VersionSpec) -> Option<Version> {
         self.versions
             .iter()
             .rev()
-            .skip_while(|v| !req.matches(&v))
+            .skip_while(|v| !spec.matches(v, None))
             .next()
             .map(|v| v.clone())
     }
 }
 
+/// Fetches the index of Yarn versions from the public npm registry.
+fn public_yarn_index() -> Fallible<YarnIndex> {
+    let spinner = progress_spinner(&format!(
+        "Fetching public registry: {}",
+        PUBLIC_YARN_VERSION_INDEX
+    ));
+
+    // Request the abbreviated metadata, which is much smaller than the full document.
+    let mut headers = reqwest::header::Headers::new();
+    headers.set_raw("Accept", "application/vnd.npm.install-v1+json");
+    let mut response = reqwest::Client::new()
+        .get(PUBLIC_YARN_VERSION_INDEX)
+        .headers(headers)
+        .send()
+        .unknown()?;
+    let serial: serial::index::YarnIndex = response.json().unknown()?;
+
+    spinner.finish_and_clear();
+    serial.into_index()
+}
+
+/// The index of available Yarn versions.
+pub struct YarnIndex {
+    /// The version tagged as `latest`, if any.
+    pub latest: Option<Version>,
+    pub versions: BTreeSet<Version>,
+}
+
 /// The index of the public Node server.
 pub struct Index {
     pub entries: BTreeMap<Version, VersionData>,
 }
 
-/// The set of available files on the public Node server for a given Node version.
+/// The set of available files on the public Node server for a given Node version,
+/// along with its release metadata.
 pub struct VersionData {
     pub files: HashSet<String>,
+    /// The LTS codename of this version (in lowercase), if it is an LTS release.
+    pub lts: Option<String>,
+    /// The release date of this version, if known.
+    pub date: Option<String>,
+}
+
+impl VersionData {
+    /// Tests whether the specified version, described by this data, satisfies
+    /// a version specification.
+    pub fn matches(&self, version: &Version, spec: &VersionSpec) -> bool {
+        spec.matches(version, self.lts.as_ref().map(String::as_str))
+    }
 }
 
 impl FromStr for Catalog {
@@ -308,3 +914,57 @@ impl FromStr for Catalog {
         Ok(serial.into_catalog()?)
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parse_validators_both() {
+        let (etag, last_modified) = parse_validators(
+            "ETag: \"5b1b3c-3d1a\"\nLast-Modified: Wed, 21 Oct 2015 07:28:00 GMT\n",
+        );
+        assert_eq!(etag, Some(EntityTag::strong(String::from("5b1b3c-3d1a"))));
+        assert_eq!(
+            last_modified,
+            Some(HttpDate::from_str("Wed, 21 Oct 2015 07:28:00 GMT").unwrap())
+        );
+    }
+
+    #[test]
+    fn parse_validators_weak_etag() {
+        let (etag, last_modified) = parse_validators("ETag: W/\"5b1b3c\"\n");
+        assert_eq!(etag, Some(EntityTag::weak(String::from("5b1b3c"))));
+        assert_eq!(last_modified, None);
+    }
+
+    #[test]
+    fn parse_validators_round_trip() {
+        let etag = EntityTag::strong(String::from("abc"));
+        let (parsed, _) = parse_validators(&format!("ETag: {}\n", etag));
+        assert_eq!(parsed, Some(etag));
+    }
+
+    #[test]
+    fn parse_validators_ignores_malformed_lines() {
+        let (etag, last_modified) =
+            parse_validators("ETag: unquoted\nLast-Modified: yesterday\nExpires: never\n");
+        assert_eq!(etag, None);
+        assert_eq!(last_modified, None);
+        assert_eq!(parse_validators(""), (None, None));
+    }
+
+    #[test]
+    fn mirror_key_includes_scheme_and_path() {
+        assert_eq!(mirror_key("https://nodejs.org/dist/"), "https-nodejs.org-dist");
+        assert_eq!(mirror_key("http://nodejs.org/dist/"), "http-nodejs.org-dist");
+        assert_eq!(
+            mirror_key("https://mirror.example.com:8080/node/dist/"),
+            "https-mirror.example.com-8080-node-dist"
+        );
+        assert_ne!(
+            mirror_key("https://mirror.example.com/node/"),
+            mirror_key("https://mirror.example.com/iojs/")
+        );
+    }
+}
@@ -1,5 +1,6 @@
 //! Provides types for working with Notion configuration files.
 
+use std::env;
 use std::str::FromStr;
 
 use lazycell::LazyCell;
@@ -12,6 +13,9 @@ use readext::ReadExt;
 use serial;
 use serial::touch;
 
+/// The root URL of the public Node server.
+const PUBLIC_NODE_SERVER_ROOT: &'static str = "https://nodejs.org/dist/";
+
 /// Lazily loaded Notion configuration settings.
 pub struct LazyConfig {
     config: LazyCell<Config>,
@@ -34,16 +38,25 @@ impl LazyConfig {
 /// Notion configuration settings.
 pub struct Config {
     pub node: Option<NodeConfig>,
+    pub network: Option<NetworkConfig>,
 }
 
 /// Notion configuration settings relating to the Node executable.
 pub struct NodeConfig {
+    /// The root URL of a mirror of the public Node server, if any.
+    pub mirror: Option<String>,
     /// The plugin for resolving Node versions, if any.
     pub resolve: Option<plugin::Resolve>,
     /// The plugin for listing the set of Node versions available on the remote server, if any.
     pub ls_remote: Option<plugin::LsRemote>,
 }
 
+/// Notion configuration settings relating to network access.
+pub struct NetworkConfig {
+    /// Whether Notion must work without going to the network.
+    pub offline: bool,
+}
+
 impl Config {
     /// Returns the current configuration settings, loaded from the filesystem.
     fn current() -> Fallible<Config> {
@@ -51,6 +64,42 @@ impl Config {
         let src = touch(&path)?.read_into_string().unknown()?;
         src.parse()
     }
+
+    /// Produces the root URL to fetch Node versions and their index from. This
+    /// is the `NOTION_NODE_MIRROR` environment variable if it is set, or else
+    /// the `[node] mirror` setting if there is one, or else the public Node
+    /// server. The URL always ends with a `/`.
+    pub fn node_mirror(&self) -> String {
+        let mirror = match (env::var("NOTION_NODE_MIRROR"), &self.node) {
+            (Ok(ref mirror), _) if !mirror.is_empty() => mirror.clone(),
+            (
+                _,
+                &Some(NodeConfig {
+                    mirror: Some(ref mirror),
+                    ..
+                }),
+            ) => mirror.clone(),
+            _ => String::from(PUBLIC_NODE_SERVER_ROOT),
+        };
+
+        if mirror.ends_with('/') {
+            mirror
+        } else {
+            format!("{}/", mirror)
+        }
+    }
+
+    /// Determines whether Notion is offline, and so must never go to the
+    /// network. This is the `NOTION_OFFLINE` environment variable if it is
+    /// set (to anything but `0` or `false`), or else the `[network] offline`
+    /// setting.
+    pub fn offline(&self) -> bool {
+        match (env::var("NOTION_OFFLINE"), &self.network) {
+            (Ok(ref offline), _) if !offline.is_empty() => offline != "0" && offline != "false",
+            (_, &Some(NetworkConfig { offline })) => offline,
+            _ => false,
+        }
+    }
 }
 
 impl FromStr for Config {
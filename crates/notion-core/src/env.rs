@@ -11,11 +11,26 @@ use path;
 /// will find Node.js executables in the installation directory for the given
 /// version of Node instead of in the Notion shim directory.
 pub fn path_for(version: &str) -> OsString {
+    path_with(vec![path::node_version_bin_dir(version).unwrap()])
+}
+
+/// Produces a modified version of the current `PATH` environment variable that
+/// will find the Yarn executables in the installation directory for the given
+/// version of Yarn, and the Node.js executables for the given version of Node,
+/// instead of in the Notion shim directory.
+pub fn path_for_yarn(node_version: &str, yarn_version: &str) -> OsString {
+    path_with(vec![
+        path::yarn_version_bin_dir(yarn_version).unwrap(),
+        path::node_version_bin_dir(node_version).unwrap(),
+    ])
+}
+
+/// Produces a modified version of the current `PATH` environment variable with
+/// the Notion shim directory removed and the given directories prepended.
+fn path_with(mut path_vec: Vec<PathBuf>) -> OsString {
     let current = env::var_os("PATH").unwrap_or(OsString::new());
     let shim_dir = &path::shim_dir().unwrap();
     let split = env::split_paths(&current).filter(|s| s != shim_dir);
-    let mut path_vec: Vec<PathBuf> = Vec::new();
-    path_vec.push(path::node_version_bin_dir(version).unwrap());
     path_vec.extend(split);
     env::join_paths(path_vec.iter()).unwrap()
 }
@@ -32,6 +32,9 @@ enum EventKind {
     Start,
     End {
         exit_code: i32,
+        /// The signal that terminated the activity, if any (on Unix).
+        #[serde(skip_serializing_if = "Option::is_none")]
+        signal: Option<i32>,
     },
     Error {
         exit_code: i32,
@@ -102,7 +105,22 @@ impl EventLog {
         self.add_event(EventKind::Start, activity_kind)
     }
     pub fn add_event_end(&mut self, activity_kind: ActivityKind, exit_code: i32) {
-        self.add_event(EventKind::End { exit_code }, activity_kind)
+        self.add_event(
+            EventKind::End {
+                exit_code,
+                signal: None,
+            },
+            activity_kind,
+        )
+    }
+    pub fn add_event_signal_end(&mut self, activity_kind: ActivityKind, exit_code: i32, signal: i32) {
+        self.add_event(
+            EventKind::End {
+                exit_code,
+                signal: Some(signal),
+            },
+            activity_kind,
+        )
     }
     pub fn add_event_error(&mut self, activity_kind: ActivityKind, error: &NotionError) {
         let exit_code = error.exit_code();
@@ -1,8 +1,21 @@
 //! Provides types for installing tools into the Notion catalog.
 
 pub mod node;
+pub mod yarn;
 
+use std::fs::{create_dir_all, read_dir, remove_dir_all, remove_file, rename, File};
+use std::io;
+use std::path::{Path, PathBuf};
+
+use notion_fail::{Fallible, ResultExt};
 use semver::Version;
+use tempfile::{Builder, TempDir};
+
+use lock::FileLock;
+use path;
+
+/// The prefix of the name of every staging directory.
+const STAGING_PREFIX: &'static str = "install-";
 
 /// The result of a requested installation.
 pub enum Installed {
@@ -27,3 +40,129 @@ impl Installed {
         }
     }
 }
+
+/// A staging directory to unpack an archive into. Its lock file (next to it,
+/// with a `.lock` extension) is held for as long as it exists, so that other
+/// processes can tell that it is still in use. The directory is deleted when
+/// dropped, so a failed install leaves nothing behind.
+pub(crate) struct StagingDir {
+    dir: Option<TempDir>,
+    lock: Option<FileLock>,
+    lock_file: PathBuf,
+}
+
+impl StagingDir {
+    /// Produces the path of the staging directory.
+    pub(crate) fn path(&self) -> &Path {
+        self.dir.as_ref().unwrap().path()
+    }
+}
+
+impl Drop for StagingDir {
+    fn drop(&mut self) {
+        // Remove the directory before releasing its lock, so that it is never
+        // left unlocked while it exists. Removing the lock file is only
+        // best-effort, since another process may be cleaning it up too.
+        drop(self.dir.take());
+        drop(self.lock.take());
+        let _ = remove_file(&self.lock_file);
+    }
+}
+
+/// Produces the path of the lock file of a staging directory.
+fn staging_lock_file(dir: &Path) -> PathBuf {
+    let mut file_name = dir.file_name().unwrap().to_os_string();
+    file_name.push(".lock");
+    dir.with_file_name(file_name)
+}
+
+/// Creates a fresh staging directory to unpack an archive into, after removing
+/// any staging directories abandoned by interrupted installs.
+pub(crate) fn staging_dir() -> Fallible<StagingDir> {
+    let tmp_dir = path::tmp_dir()?;
+    create_dir_all(&tmp_dir).unknown()?;
+
+    // Cleaning up and creating a staging directory (along with locking it)
+    // happen under one lock, so that a fresh directory is never mistaken for
+    // an abandoned one before its own lock is held.
+    let _lock = FileLock::acquire(&path::staging_lock_file()?)?;
+    remove_abandoned_staging_dirs(&tmp_dir)?;
+
+    let dir = Builder::new()
+        .prefix(STAGING_PREFIX)
+        .tempdir_in(&tmp_dir)
+        .unknown()?;
+    let lock_file = staging_lock_file(dir.path());
+    let lock = FileLock::acquire(&lock_file)?;
+
+    Ok(StagingDir {
+        dir: Some(dir),
+        lock: Some(lock),
+        lock_file: lock_file,
+    })
+}
+
+/// Removes the staging directories whose lock isn't held, which means that
+/// the install using them was interrupted.
+fn remove_abandoned_staging_dirs(tmp_dir: &Path) -> Fallible<()> {
+    for entry in read_dir(tmp_dir).unknown()? {
+        let entry = entry.unknown()?;
+
+        if !entry.file_type().unknown()?.is_dir()
+            || !entry.file_name().to_string_lossy().starts_with(STAGING_PREFIX)
+        {
+            continue;
+        }
+
+        let dir = entry.path();
+        let lock_file = staging_lock_file(&dir);
+        if let Some(lock) = FileLock::try_acquire(&lock_file)? {
+            // This is only best-effort, and is retried by the next install.
+            let _ = remove_dir_all(&dir);
+            drop(lock);
+            let _ = remove_file(&lock_file);
+        }
+    }
+
+    Ok(())
+}
+
+/// Flushes a fully unpacked directory tree to disk and then moves it into its
+/// final location, so that the destination directory is either absent or
+/// complete. If the destination already exists (because an earlier install
+/// was interrupted after this step), it is left as is.
+pub(crate) fn commit(unpacked: &Path, dest: &Path) -> Fallible<()> {
+    sync_tree(unpacked).unknown()?;
+
+    if dest.is_dir() {
+        return Ok(());
+    }
+
+    let parent = dest.parent().unwrap();
+    create_dir_all(parent).unknown()?;
+    rename(unpacked, dest).unknown()?;
+    sync_dir(parent).unknown()
+}
+
+/// Flushes all the files in a directory tree to disk.
+fn sync_tree(dir: &Path) -> io::Result<()> {
+    for entry in read_dir(dir)? {
+        let entry = entry?;
+        let file_type = entry.file_type()?;
+        if file_type.is_dir() {
+            sync_tree(&entry.path())?;
+        } else if file_type.is_file() {
+            File::open(entry.path())?.sync_all()?;
+        }
+    }
+    sync_dir(dir)
+}
+
+/// Flushes a directory's entries to disk. Directories can't be opened as files
+/// on Windows, where this is a no-op.
+fn sync_dir(dir: &Path) -> io::Result<()> {
+    if cfg!(unix) {
+        File::open(dir)?.sync_all()?;
+    }
+    Ok(())
+}
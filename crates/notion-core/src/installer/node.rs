@@ -1,53 +1,274 @@
 //! Provides the `Installer` type, which represents a provisioned Node installer.
 
-use std::fs::{rename, File};
+use std::fs::{create_dir_all, read_to_string, remove_file, write, File};
+use std::io;
+use std::path::{Path, PathBuf};
 use std::string::ToString;
 
-use super::Installed;
-use catalog::NodeCatalog;
+use super::{commit, staging_dir, Installed};
+use catalog::{mirror_key, NodeCatalog, OfflineError};
+use lock::FileLock;
 use node_archive::{self, Archive};
 use path;
-use style::{progress_bar, Action};
+use style::{display_warning, progress_bar, Action};
 
-use notion_fail::{Fallible, ResultExt};
+use notion_fail::{Fallible, NotionFail, ResultExt};
+use reqwest;
 use semver::Version;
+use tempfile::NamedTempFile;
 
-const PUBLIC_NODE_SERVER_ROOT: &'static str = "https://nodejs.org/dist/";
+/// Thrown when a Node archive does not match the checksum published for it.
+#[derive(Fail, Debug)]
+#[fail(display = "Checksum mismatch for {}: expected {}, found {}", file, expected, actual)]
+struct ChecksumMismatchError {
+    file: String,
+    expected: String,
+    actual: String,
+}
+
+impl NotionFail for ChecksumMismatchError {
+    fn is_user_friendly(&self) -> bool {
+        true
+    }
+    fn exit_code(&self) -> i32 {
+        5
+    }
+}
+
+/// Thrown when no checksum is published for a Node archive.
+#[derive(Fail, Debug)]
+#[fail(display = "No checksum found for {} at {}", file, url)]
+struct NoChecksumError {
+    file: String,
+    url: String,
+}
+
+impl NotionFail for NoChecksumError {
+    fn is_user_friendly(&self) -> bool {
+        true
+    }
+    fn exit_code(&self) -> i32 {
+        5
+    }
+}
+
+/// Thrown when an archive file to install from can't be opened.
+#[derive(Fail, Debug)]
+#[fail(display = "Could not open archive {}", file)]
+struct NoArchiveFileError {
+    file: String,
+}
+
+impl NotionFail for NoArchiveFileError {
+    fn is_user_friendly(&self) -> bool {
+        true
+    }
+    fn exit_code(&self) -> i32 {
+        9
+    }
+}
+
+/// Thrown when the Node version of an archive file can't be determined.
+#[derive(Fail, Debug)]
+#[fail(display = "Could not determine the Node version of {}", file)]
+struct UnknownArchiveVersionError {
+    file: String,
+}
+
+impl NotionFail for UnknownArchiveVersionError {
+    fn is_user_friendly(&self) -> bool {
+        true
+    }
+    fn exit_code(&self) -> i32 {
+        9
+    }
+}
+
+/// Thrown when an archive file is not the expected Node version for this platform.
+#[derive(Fail, Debug)]
+#[fail(display = "{} is not the expected Node archive: expected {}, found {}", file, expected,
+       actual)]
+struct ArchiveMismatchError {
+    file: String,
+    expected: String,
+    actual: String,
+}
+
+impl NotionFail for ArchiveMismatchError {
+    fn is_user_friendly(&self) -> bool {
+        true
+    }
+    fn exit_code(&self) -> i32 {
+        9
+    }
+}
 
 /// A provisioned Node installer.
 pub struct Installer {
     archive: Box<Archive>,
     version: Version,
+    /// The expected SHA-256 checksum of the archive, if known.
+    checksum: Option<String>,
+    /// The location of the cached archive, if known.
+    cache_file: Option<PathBuf>,
+    /// The lock on installing this version, which is held for the lifetime of
+    /// the installer so that only one process installs a version at a time.
+    _lock: FileLock,
 }
 
 impl Installer {
-    /// Provision an `Installer` from the public Node distributor (`https://nodejs.org`).
-    pub fn public(version: Version) -> Fallible<Self> {
+    /// Provision an `Installer` from the public Node distributor (`https://nodejs.org`),
+    /// or a mirror of it, given the root URL of its distribution directory.
+    pub fn public(version: Version, mirror: &str) -> Fallible<Self> {
+        let lock = install_lock(&version)?;
         let archive_file = path::archive_file(&version.to_string());
-        let url = format!("{}v{}/{}", PUBLIC_NODE_SERVER_ROOT, version, &archive_file);
-        Installer::remote(version, &url)
+        let url = format!("{}v{}/{}", mirror, version, &archive_file);
+        let checksum = public_checksum(&version, mirror)?;
+        Installer::fetch(version, &url, Some(checksum), lock)
     }
 
     /// Provision an `Installer` from a remote distributor.
     pub fn remote(version: Version, url: &str) -> Fallible<Self> {
+        let lock = install_lock(&version)?;
+        Installer::fetch(version, url, None, lock)
+    }
+
+    /// Provision an `Installer` from the cache if available, or else by downloading
+    /// from the specified URL.
+    fn fetch(version: Version, url: &str, checksum: Option<String>, lock: FileLock) -> Fallible<Self> {
         let archive_file = path::archive_file(&version.to_string());
         let cache_file = path::node_cache_dir()?.join(&archive_file);
 
         if cache_file.is_file() {
-            return Installer::cached(version, File::open(cache_file).unknown()?);
+            let file = File::open(&cache_file).unknown()?;
+            let installer = Installer::load(version, file, checksum, lock);
+
+            if let Err(ref err) = installer {
+                if err.downcast_ref::<ChecksumMismatchError>().is_some() {
+                    remove_file(&cache_file).unknown()?;
+                }
+            }
+
+            return installer.map(|installer| Installer {
+                cache_file: Some(cache_file),
+                ..installer
+            });
         }
 
         Ok(Installer {
             archive: node_archive::fetch(url, &cache_file).unknown()?,
             version: version,
+            checksum: checksum,
+            cache_file: Some(cache_file),
+            _lock: lock,
         })
     }
 
-    /// Provision an `Installer` from the filesystem.
-    pub fn cached(version: Version, file: File) -> Fallible<Self> {
+    /// Provision an `Installer` from the cache without going to the network,
+    /// verifying the archive against the checksums cached from the specified
+    /// mirror, if any.
+    pub fn offline(version: Version, mirror: &str) -> Fallible<Self> {
+        let lock = install_lock(&version)?;
+        let cache_file = path::node_cache_dir()?.join(path::archive_file(&version.to_string()));
+
+        if !cache_file.is_file() {
+            throw!(OfflineError {
+                what: format!("Node v{}", version),
+            });
+        }
+
+        let checksum = local_checksum(&cache_file, &version, mirror)?;
+        let file = File::open(&cache_file).unknown()?;
+        let installer = Installer::load(version, file, checksum, lock)?;
+
+        Ok(Installer {
+            cache_file: Some(cache_file),
+            ..installer
+        })
+    }
+
+    /// Provision an `Installer` from the filesystem, verifying the archive against
+    /// the expected checksum, if any.
+    pub fn cached(version: Version, file: File, checksum: Option<String>) -> Fallible<Self> {
+        let lock = install_lock(&version)?;
+        Installer::load(version, file, checksum, lock)
+    }
+
+    /// Provision an `Installer` from an archive file outside the cache (such as
+    /// a vendored toolchain). Unless the version is specified, it is determined
+    /// from the archive's top-level directory. The archive is verified against
+    /// a `SHASUMS256.txt` file next to it or cached from the specified mirror,
+    /// if there is one (otherwise, a warning is displayed), and then copied
+    /// into the cache.
+    pub fn local(archive: &Path, version: Option<Version>, mirror: &str) -> Fallible<Self> {
+        let file_name = archive.to_string_lossy().to_string();
+        let mut source = File::open(archive).with_context(|_| NoArchiveFileError {
+            file: file_name.clone(),
+        })?;
+        let root_dir = node_archive::root_dir(&mut source).unknown()?;
+
+        let version = match (version, root_dir.as_ref().and_then(|root| parse_root_dir(root))) {
+            (Some(version), _) | (None, Some(version)) => version,
+            (None, None) => {
+                throw!(UnknownArchiveVersionError { file: file_name });
+            }
+        };
+
+        let version_string = version.to_string();
+        let expected_root_dir = path::archive_root_dir(&version_string);
+        if root_dir.as_ref() != Some(&expected_root_dir) {
+            throw!(ArchiveMismatchError {
+                file: file_name,
+                expected: expected_root_dir,
+                actual: root_dir.unwrap_or_default(),
+            });
+        }
+
+        let lock = install_lock(&version)?;
+
+        let checksum = local_checksum(archive, &version, mirror)?;
+        match checksum {
+            Some(ref expected) => {
+                let actual = node_archive::checksum(&mut source).unknown()?;
+                verify(&version, expected, &actual)?;
+            }
+            None => {
+                display_warning(&format!(
+                    "No SHASUMS256.txt found for {}, so it was not verified",
+                    file_name
+                ));
+            }
+        }
+
+        let cache_dir = path::node_cache_dir()?;
+        let cache_file = cache_dir.join(path::archive_file(&version_string));
+        create_dir_all(&cache_dir).unknown()?;
+        let mut cached = NamedTempFile::new_in(&cache_dir).unknown()?;
+        io::copy(&mut source, &mut cached).unknown()?;
+        cached.as_file().sync_all().unknown()?;
+        cached.persist(&cache_file).unknown()?;
+
+        Ok(Installer {
+            archive: node_archive::load(File::open(&cache_file).unknown()?).unknown()?,
+            version: version,
+            checksum: checksum,
+            cache_file: Some(cache_file),
+            _lock: lock,
+        })
+    }
+
+    fn load(version: Version, mut file: File, checksum: Option<String>, lock: FileLock) -> Fallible<Self> {
+        if let Some(ref expected) = checksum {
+            let actual = node_archive::checksum(&mut file).unknown()?;
+            verify(&version, expected, &actual)?;
+        }
+
         Ok(Installer {
             archive: node_archive::load(file).unknown()?,
             version: version,
+            checksum: checksum,
+            cache_file: None,
+            _lock: lock,
         })
     }
 
@@ -56,14 +277,19 @@ impl Installer {
         &self.version
     }
 
-    /// Installs this version of Node. (It is left to the responsibility of the `NodeCatalog`
-    /// to update its state after installation succeeds.)
+    /// Installs this version of Node. The archive is unpacked into a staging
+    /// directory and only moved into place once it is complete. (It is left to
+    /// the responsibility of the `NodeCatalog` to update its state after
+    /// installation succeeds.)
     pub fn install(self, catalog: &NodeCatalog) -> Fallible<Installed> {
-        if catalog.contains(&self.version) {
+        let version_string = self.version.to_string();
+
+        // Another process may have installed this version while we waited for the lock.
+        if catalog.contains(&self.version) || path::node_version_dir(&version_string)?.is_dir() {
             return Ok(Installed::Already(self.version));
         }
 
-        let dest = path::node_versions_dir()?;
+        let staging = staging_dir()?;
         let bar = progress_bar(
             Action::Installing,
             &format!("v{}", self.version),
@@ -72,19 +298,137 @@ impl Installer {
                 .unwrap_or(self.archive.compressed_size()),
         );
 
-        self.archive
-            .unpack(&dest, &mut |_, read| {
+        let actual = self.archive
+            .unpack(staging.path(), &mut |_, read| {
                 bar.inc(read as u64);
             })
             .unknown()?;
 
-        let version_string = self.version.to_string();
-        rename(
-            dest.join(path::archive_root_dir(&version_string)),
-            path::node_version_dir(&version_string)?,
-        ).unknown()?;
+        let root = staging.path().join(path::archive_root_dir(&version_string));
+
+        if let Some(ref expected) = self.checksum {
+            if let Err(err) = verify(&self.version, expected, &actual) {
+                bar.finish_and_clear();
+                if let Some(ref cache_file) = self.cache_file {
+                    remove_file(cache_file).unknown()?;
+                }
+                return Err(err);
+            }
+        }
+
+        commit(&root, &path::node_version_dir(&version_string)?)?;
 
         bar.finish_and_clear();
         Ok(Installed::Now(self.version))
     }
 }
+
+/// Acquires the lock on installing the specified version of Node, waiting
+/// for any other process that is installing it to finish.
+fn install_lock(version: &Version) -> Fallible<FileLock> {
+    FileLock::acquire(&path::node_install_lock_file(&version.to_string())?)
+}
+
+/// Checks an archive's checksum against the expected checksum.
+fn verify(version: &Version, expected: &str, actual: &str) -> Fallible<()> {
+    if !expected.eq_ignore_ascii_case(actual) {
+        throw!(ChecksumMismatchError {
+            file: path::archive_file(&version.to_string()),
+            expected: expected.to_string(),
+            actual: actual.to_string(),
+        });
+    }
+    Ok(())
+}
+
+/// Produces the checksum of a Node version's archive on the public Node server,
+/// as published in its `SHASUMS256.txt` file. The file is cached alongside the
+/// archive (keyed by the mirror, like the index) so that cached archives can be
+/// verified without going to the network.
+fn public_checksum(version: &Version, mirror: &str) -> Fallible<String> {
+    let version_string = version.to_string();
+    let archive_file = path::archive_file(&version_string);
+    let url = format!("{}v{}/SHASUMS256.txt", mirror, version);
+    let shasums_file =
+        path::node_cache_dir()?.join(path::shasums_file(&version_string, &mirror_key(mirror)));
+
+    let shasums = if shasums_file.is_file() {
+        read_to_string(&shasums_file).unknown()?
+    } else {
+        let mut response = reqwest::get(&url).unknown()?;
+
+        if !response.status().is_success() {
+            throw!(NoChecksumError {
+                file: archive_file,
+                url: url,
+            });
+        }
+
+        let text = response.text().unknown()?;
+        write(&shasums_file, &text).unknown()?;
+        text
+    };
+
+    if let Some(checksum) = find_checksum(&shasums, &archive_file) {
+        Ok(checksum)
+    } else {
+        throw!(NoChecksumError {
+            file: archive_file,
+            url: url,
+        });
+    }
+}
+
+/// Determines the Node version of an archive from its top-level directory,
+/// which has the form `node-v<version>-<os>-<arch>`.
+fn parse_root_dir(root_dir: &str) -> Option<Version> {
+    if !root_dir.starts_with("node-v") {
+        return None;
+    }
+
+    // The version itself may contain hyphens (e.g., `10.0.0-rc.1`).
+    let mut parts = root_dir["node-v".len()..].rsplitn(3, '-');
+    match (parts.next(), parts.next(), parts.next()) {
+        (Some(_arch), Some(_os), Some(version)) => Version::parse(version).ok(),
+        _ => None,
+    }
+}
+
+/// Produces the checksum of a Node version's archive from a `SHASUMS256.txt`
+/// file next to the specified archive file or cached from the specified
+/// mirror, if either exists.
+fn local_checksum(archive: &Path, version: &Version, mirror: &str) -> Fallible<Option<String>> {
+    let version_string = version.to_string();
+    let archive_file = path::archive_file(&version_string);
+    let candidates = vec![
+        archive.with_file_name("SHASUMS256.txt"),
+        path::node_cache_dir()?.join(path::shasums_file(&version_string, &mirror_key(mirror))),
+    ];
+
+    for shasums_file in candidates {
+        if shasums_file.is_file() {
+            let shasums = read_to_string(&shasums_file).unknown()?;
+            if let Some(checksum) = find_checksum(&shasums, &archive_file) {
+                return Ok(Some(checksum));
+            }
+        }
+    }
+
+    Ok(None)
+}
+
+/// Finds the checksum of the specified archive in the contents of a
+/// `SHASUMS256.txt` file.
+pub(crate) fn find_checksum(shasums: &str, archive_file: &str) -> Option<String> {
+    // Each line of the file has the form `<checksum>  <filename>`.
+    shasums
+        .lines()
+        .filter_map(|line| {
+            let mut words = line.split_whitespace();
+            match (words.next(), words.next()) {
+                (Some(checksum), Some(file)) if file == archive_file => Some(checksum.to_string()),
+                _ => None,
+            }
+        })
+        .next()
+}
@@ -0,0 +1,103 @@
+//! Provides the `Installer` type, which represents a provisioned Yarn installer.
+
+use std::fs::{create_dir_all, File};
+use std::string::ToString;
+
+use super::{commit, staging_dir, Installed};
+use catalog::YarnCatalog;
+use lock::FileLock;
+use node_archive::{Archive, Tarball};
+use path;
+use style::{progress_bar, Action};
+
+use notion_fail::{Fallible, ResultExt};
+use semver::Version;
+
+const PUBLIC_YARN_SERVER_ROOT: &'static str = "https://github.com/yarnpkg/yarn/releases/download/";
+
+/// A provisioned Yarn installer.
+pub struct Installer {
+    archive: Box<Archive>,
+    version: Version,
+    /// The lock on installing this version, held for the lifetime of the installer.
+    _lock: FileLock,
+}
+
+impl Installer {
+    /// Provision an `Installer` from the public Yarn distributor (the Yarn
+    /// GitHub releases), or from the cache if it was downloaded before.
+    pub fn public(version: Version) -> Fallible<Self> {
+        let lock = install_lock(&version)?;
+        let archive_file = path::yarn_archive_file(&version.to_string());
+        let url = format!("{}v{}/{}", PUBLIC_YARN_SERVER_ROOT, version, &archive_file);
+        let cache_dir = path::yarn_cache_dir()?;
+        let cache_file = cache_dir.join(&archive_file);
+
+        if cache_file.is_file() {
+            return Installer::load(version, File::open(&cache_file).unknown()?, lock);
+        }
+
+        create_dir_all(&cache_dir).unknown()?;
+
+        Ok(Installer {
+            archive: Box::new(Tarball::fetch(&url, &cache_file).unknown()?),
+            version: version,
+            _lock: lock,
+        })
+    }
+
+    /// Provision an `Installer` from the filesystem.
+    pub fn cached(version: Version, file: File) -> Fallible<Self> {
+        let lock = install_lock(&version)?;
+        Installer::load(version, file, lock)
+    }
+
+    fn load(version: Version, file: File, lock: FileLock) -> Fallible<Self> {
+        Ok(Installer {
+            archive: Box::new(Tarball::load(file).unknown()?),
+            version: version,
+            _lock: lock,
+        })
+    }
+
+    /// Installs this version of Yarn, by way of a staging directory like Node
+    /// installs. (It is left to the responsibility of the `YarnCatalog`
+    /// to update its state after installation succeeds.)
+    pub fn install(self, catalog: &YarnCatalog) -> Fallible<Installed> {
+        let version_string = self.version.to_string();
+
+        // Another process may have installed this version while we waited for the lock.
+        if catalog.contains(&self.version) || path::yarn_version_dir(&version_string)?.is_dir() {
+            return Ok(Installed::Already(self.version));
+        }
+
+        let staging = staging_dir()?;
+        let bar = progress_bar(
+            Action::Installing,
+            &format!("yarn v{}", self.version),
+            self.archive
+                .uncompressed_size()
+                .unwrap_or(self.archive.compressed_size()),
+        );
+
+        self.archive
+            .unpack(staging.path(), &mut |_, read| {
+                bar.inc(read as u64);
+            })
+            .unknown()?;
+
+        commit(
+            &staging.path().join(path::yarn_archive_root_dir(&version_string)),
+            &path::yarn_version_dir(&version_string)?,
+        )?;
+
+        bar.finish_and_clear();
+        Ok(Installed::Now(self.version))
+    }
+}
+
+/// Acquires the lock on installing the specified version of Yarn, waiting
+/// for any other process that is installing it to finish.
+fn install_lock(version: &Version) -> Fallible<FileLock> {
+    FileLock::acquire(&path::yarn_install_lock_file(&version.to_string())?)
+}
@@ -4,6 +4,7 @@
 
 extern crate cmdline_words_parser;
 extern crate console;
+extern crate fs2;
 extern crate indicatif;
 extern crate lazycell;
 extern crate node_archive;
@@ -21,11 +22,16 @@ extern crate serde_derive;
 
 extern crate winfolder;
 
+#[cfg(unix)]
+extern crate libc;
+
+pub mod cache;
 pub mod catalog;
 pub mod config;
 pub mod env;
 mod event;
 mod installer;
+mod lock;
 pub mod manifest;
 pub mod monitor;
 pub mod path;
@@ -33,8 +39,10 @@ mod plugin;
 pub mod project;
 pub mod serial;
 pub mod session;
+pub mod shim;
 pub mod style;
 pub mod tool;
+pub mod version;
 
 extern crate failure;
 #[macro_use]
@@ -0,0 +1,52 @@
+//! Provides the `FileLock` type, an advisory lock that serializes access to
+//! shared state (such as the catalog) across concurrent Notion processes.
+
+use std::fs::{create_dir_all, File, OpenOptions};
+use std::path::Path;
+
+use fs2::{lock_contended_error, FileExt};
+use notion_fail::{Fallible, ResultExt};
+
+/// An exclusive advisory lock on a file, which is released when dropped.
+pub(crate) struct FileLock {
+    file: File,
+}
+
+impl FileLock {
+    /// Acquires the lock on the specified lock file, creating the file if
+    /// necessary and blocking until no other process holds the lock.
+    pub(crate) fn acquire(path: &Path) -> Fallible<FileLock> {
+        let file = open(path)?;
+        file.lock_exclusive().unknown()?;
+        Ok(FileLock { file: file })
+    }
+
+    /// Acquires the lock on the specified lock file, creating the file if
+    /// necessary, unless another process holds the lock, in which case this
+    /// produces `None` instead of blocking.
+    pub(crate) fn try_acquire(path: &Path) -> Fallible<Option<FileLock>> {
+        let file = open(path)?;
+        match file.try_lock_exclusive() {
+            Ok(()) => Ok(Some(FileLock { file: file })),
+            Err(ref err) if err.kind() == lock_contended_error().kind() => Ok(None),
+            Err(err) => Err(err).unknown(),
+        }
+    }
+}
+
+fn open(path: &Path) -> Fallible<File> {
+    create_dir_all(path.parent().unwrap()).unknown()?;
+    OpenOptions::new()
+        .read(true)
+        .write(true)
+        .create(true)
+        .open(path)
+        .unknown()
+}
+
+impl Drop for FileLock {
+    fn drop(&mut self) {
+        // Closing the file releases the lock anyway, so a failure here is harmless.
+        let _ = self.file.unlock();
+    }
+}
@@ -1,21 +1,29 @@
 //! Provides the `Manifest` type, which represents a Node manifest file (`package.json`).
 
 use std::collections::HashMap;
-use std::fs::File;
+use std::fs::{read_to_string, write, File};
 use std::path::Path;
 
-use notion_fail::{Fallible, ResultExt};
-use semver::VersionReq;
-use serde_json;
+use notion_fail::{FailExt, Fallible, ResultExt};
+use serde::Serialize;
+use serde_json::{self, Map, Serializer, Value};
+use serde_json::ser::PrettyFormatter;
 
 use serial;
+use version::VersionSpec;
+
+#[derive(Fail, Debug)]
+#[fail(display = "Unexpected non-object value for '{}' in package.json", key)]
+struct NotAnObjectError {
+    key: String,
+}
 
 /// A Node manifest file.
 pub struct Manifest {
     /// The requested version of Node, under the `notion.node` key.
-    pub node: VersionReq,
+    pub node: Option<VersionSpec>,
     /// The requested version of Yarn, under the `notion.yarn` key.
-    pub yarn: Option<VersionReq>,
+    pub yarn: Option<VersionSpec>,
     /// The `dependencies` section.
     pub dependencies: HashMap<String, String>,
     /// The command to run a plugin for events, under the `notion.events_plugin` key.
@@ -24,9 +32,82 @@ pub struct Manifest {
 
 impl Manifest {
     /// Loads and parses a Node manifest for the project rooted at the specified path.
-    pub fn for_dir(project_root: &Path) -> Fallible<Option<Manifest>> {
+    pub fn for_dir(project_root: &Path) -> Fallible<Manifest> {
         let file = File::open(project_root.join("package.json")).unknown()?;
         let serial: serial::manifest::Manifest = serde_json::de::from_reader(file).unknown()?;
         serial.into_manifest()
     }
+
+    /// Sets the `notion.node` key in the Node manifest for the project rooted
+    /// at the specified path, creating the `notion` section if necessary. The
+    /// order of keys, the indentation, and all unrelated fields are preserved.
+    pub fn update_node(project_root: &Path, version: &str) -> Fallible<()> {
+        Manifest::update_tool(project_root, "node", version)
+    }
+
+    /// Sets the `notion.yarn` key in the Node manifest for the project rooted
+    /// at the specified path, in the same way as `update_node`.
+    pub fn update_yarn(project_root: &Path, version: &str) -> Fallible<()> {
+        Manifest::update_tool(project_root, "yarn", version)
+    }
+
+    fn update_tool(project_root: &Path, tool: &str, version: &str) -> Fallible<()> {
+        let file = project_root.join("package.json");
+        let src = read_to_string(&file).unknown()?;
+        let mut json: Value = serde_json::from_str(&src).unknown()?;
+
+        {
+            let root = match json.as_object_mut() {
+                Some(root) => root,
+                None => {
+                    throw!(NotAnObjectError { key: String::from("<root>") }.unknown());
+                }
+            };
+
+            if !root.contains_key("notion") {
+                root.insert(String::from("notion"), Value::Object(Map::new()));
+            }
+
+            let notion = match root.get_mut("notion").and_then(|notion| notion.as_object_mut()) {
+                Some(notion) => notion,
+                None => {
+                    throw!(NotAnObjectError { key: String::from("notion") }.unknown());
+                }
+            };
+
+            // Update an existing key in place, since re-inserting it could move it.
+            if let Some(existing) = notion.get_mut(tool) {
+                *existing = Value::String(version.to_string());
+            }
+            if !notion.contains_key(tool) {
+                notion.insert(tool.to_string(), Value::String(version.to_string()));
+            }
+        }
+
+        let indent = detect_indent(&src);
+        let mut out = Vec::new();
+        {
+            let formatter = PrettyFormatter::with_indent(indent.as_bytes());
+            let mut serializer = Serializer::with_formatter(&mut out, formatter);
+            json.serialize(&mut serializer).unknown()?;
+        }
+        if src.ends_with('\n') {
+            out.push(b'\n');
+        }
+
+        write(&file, out).unknown()
+    }
+}
+
+/// Determines the indentation used in a JSON source file, defaulting to two spaces.
+fn detect_indent(src: &str) -> String {
+    src.lines()
+        .map(|line| {
+            line.chars()
+                .take_while(|&c| c == ' ' || c == '\t')
+                .collect::<String>()
+        })
+        .filter(|indent| !indent.is_empty())
+        .next()
+        .unwrap_or(String::from("  "))
 }
@@ -19,6 +19,20 @@ cfg_if! {
     }
 }
 
+use std::env;
+use std::path::PathBuf;
+
+/// Produces the directory named by the `NOTION_HOME` environment variable, if
+/// it is set. It overrides the standard location of every file and directory
+/// in the layout, so that a custom install location (or an isolated test
+/// home) keeps the shims, catalog and versions together.
+fn notion_home_override() -> Option<PathBuf> {
+    match env::var_os("NOTION_HOME") {
+        Some(ref home) if !home.is_empty() => Some(PathBuf::from(home)),
+        _ => None,
+    }
+}
+
 pub fn archive_file(version: &str) -> String {
     format!("{}.{}", archive_root_dir(version), archive_extension())
 }
@@ -26,3 +40,23 @@ pub fn archive_file(version: &str) -> String {
 pub fn archive_root_dir(version: &str) -> String {
     format!("node-v{}-{}-{}", version, OS, ARCH)
 }
+
+pub fn shasums_file(version: &str, mirror_key: &str) -> String {
+    format!("{}{}.txt", shasums_file_prefix(version), mirror_key)
+}
+
+/// The common prefix of the cached `SHASUMS256.txt` files of every mirror for
+/// the specified Node version.
+pub fn shasums_file_prefix(version: &str) -> String {
+    format!("node-v{}-SHASUMS256-", version)
+}
+
+// Yarn is distributed as the same tarball on every platform.
+
+pub fn yarn_archive_file(version: &str) -> String {
+    format!("{}.tar.gz", yarn_archive_root_dir(version))
+}
+
+pub fn yarn_archive_root_dir(version: &str) -> String {
+    format!("yarn-v{}", version)
+}
@@ -38,39 +38,91 @@ cfg_if! {
     } else if #[cfg(target_arch = "x86_64")] {
         /// The system architecture component of a Node distribution tarball's name.
         pub const ARCH: &'static str = "x64";
+    } else if #[cfg(target_arch = "aarch64")] {
+        /// The system architecture component of a Node distribution tarball's name.
+        pub const ARCH: &'static str = "arm64";
+    } else if #[cfg(target_arch = "arm")] {
+        /// The system architecture component of a Node distribution tarball's name.
+        pub const ARCH: &'static str = "armv7l";
+    } else if #[cfg(all(target_arch = "powerpc64", target_endian = "little"))] {
+        /// The system architecture component of a Node distribution tarball's name.
+        pub const ARCH: &'static str = "ppc64le";
+    } else if #[cfg(target_arch = "s390x")] {
+        /// The system architecture component of a Node distribution tarball's name.
+        pub const ARCH: &'static str = "s390x";
+    } else {
+        compile_error!("Unsupported target_arch variant of unix (expected 'x86', 'x64', 'arm64', \
+                        'armv7l', 'ppc64le' or 's390x').");
+    }
+}
+
+/// The key identifying this platform's Node distribution tarball in the
+/// `files` list of an entry in the public Node index (e.g., `linux-arm64` or
+/// `osx-arm64-tar`), which uses the same architecture names as the tarballs.
+pub fn platform_key() -> String {
+    if cfg!(target_os = "macos") {
+        format!("osx-{}-tar", ARCH)
     } else {
-        compile_error!("Unsupported target_arch variant of unix (expected 'x86' or 'x64').");
+        format!("{}-{}", OS, ARCH)
     }
 }
 
 // ~/
-//     .notion/
-//         cache/                                          cache_dir
-//             node/                                       node_cache_dir
-//                 node-dist-v4.8.4-linux-x64.tar.gz       archive_file("4.8.4")
+//     .notion/                                                       (or $NOTION_HOME)
+//         cache/                                                     cache_dir
+//             node/                                                  node_cache_dir
+//                 node-dist-v4.8.4-linux-x64.tar.gz                  archive_file("4.8.4")
+//                 node-v4.8.4-SHASUMS256-https-nodejs.org-dist.txt   shasums_file("4.8.4", "https-nodejs.org-dist")
+//                 index-https-nodejs.org-dist.json                   node_index_file("https-nodejs.org-dist")
+//                 index-https-nodejs.org-dist.json.expires           node_index_expiry_file("https-nodejs.org-dist")
+//                 index-https-nodejs.org-dist.json.validators        node_index_validators_file("https-nodejs.org-dist")
 //                 node-dist-v6.11.3-linux-x64.tar.gz
 //                 node-dist-v8.6.0-linux-x64.tar.gz
 //                 ...
-//         versions/                                       versions_dir
-//             node/                                       node_versions_dir
-//                 4.8.4/                                  node_version_dir("4.8.4")
-//                   bin/                                  node_version_bin_dir("4.8.4")
+//             yarn/                                                  yarn_cache_dir
+//                 yarn-v1.3.2.tar.gz                                 yarn_archive_file("1.3.2")
+//                 ...
+//         versions/                                                  versions_dir
+//             node/                                                  node_versions_dir
+//                 4.8.4/                                             node_version_dir("4.8.4")
+//                   bin/                                             node_version_bin_dir("4.8.4")
 //                 6.11.3/
 //                 8.6.0/
 //                 ...
-//         bin/                                            bin_dir
-//             notion                                      notion_file
-//         shim/                                           shim_dir
-//             node                                        shim_file("node")
+//             yarn/                                                  yarn_versions_dir
+//                 1.3.2/                                             yarn_version_dir("1.3.2")
+//                   bin/                                             yarn_version_bin_dir("1.3.2")
+//                 ...
+//         tmp/                                                       tmp_dir
+//             staging.lock                                           staging_lock_file
+//             install-XXXXXX/                                        (staging directory for an install)
+//             install-XXXXXX.lock                                    (held while the install is running)
+//         locks/                                                     locks_dir
+//             node-8.6.0.lock                                        node_install_lock_file("8.6.0")
+//             yarn-1.3.2.lock                                        yarn_install_lock_file("1.3.2")
+//             ...
+//         usage/                                                     usage_dir
+//             node-8.6.0                                             node_usage_file("8.6.0")
+//             ...
+//         bin/                                                       bin_dir
+//             notion                                                 notion_file
+//         shim/                                                      shim_dir
+//             node                                                   shim_file("node")
+//             yarn                                                   shim_file("yarn")
 //             npm
 //             npx
 //             ...
-//         launchbin                                       launchbin_file
-//         launchscript                                    launchscript_file
-//         config.toml                                     user_config_file
-//         catalog.toml                                    user_catalog_file
+//         launchbin                                                  launchbin_file
+//         launchscript                                               launchscript_file
+//         config.toml                                                user_config_file
+//         catalog.toml                                               user_catalog_file
+//         catalog.toml.lock                                          user_catalog_lock_file
 
 fn notion_home() -> Fallible<PathBuf> {
+    if let Some(home) = super::notion_home_override() {
+        return Ok(home);
+    }
+
     let home = env::home_dir().ok_or(NoHomeEnvVar)?;
     Ok(home.join(".notion"))
 }
@@ -83,12 +135,20 @@ pub fn node_cache_dir() -> Fallible<PathBuf> {
     Ok(cache_dir()?.join("node"))
 }
 
-pub fn node_index_file() -> Fallible<PathBuf> {
-    Ok(node_cache_dir()?.join("index.json"))
+pub fn node_index_file(mirror_key: &str) -> Fallible<PathBuf> {
+    Ok(node_cache_dir()?.join(format!("index-{}.json", mirror_key)))
+}
+
+pub fn node_index_expiry_file(mirror_key: &str) -> Fallible<PathBuf> {
+    Ok(node_cache_dir()?.join(format!("index-{}.json.expires", mirror_key)))
+}
+
+pub fn node_index_validators_file(mirror_key: &str) -> Fallible<PathBuf> {
+    Ok(node_cache_dir()?.join(format!("index-{}.json.validators", mirror_key)))
 }
 
-pub fn node_index_expiry_file() -> Fallible<PathBuf> {
-    Ok(node_cache_dir()?.join("index.json.expires"))
+pub fn yarn_cache_dir() -> Fallible<PathBuf> {
+    Ok(cache_dir()?.join("yarn"))
 }
 
 pub fn archive_extension() -> String {
@@ -111,6 +171,46 @@ pub fn node_version_bin_dir(version: &str) -> Fallible<PathBuf> {
     Ok(node_version_dir(version)?.join("bin"))
 }
 
+pub fn yarn_versions_dir() -> Fallible<PathBuf> {
+    Ok(versions_dir()?.join("yarn"))
+}
+
+pub fn yarn_version_dir(version: &str) -> Fallible<PathBuf> {
+    Ok(yarn_versions_dir()?.join(version))
+}
+
+pub fn yarn_version_bin_dir(version: &str) -> Fallible<PathBuf> {
+    Ok(yarn_version_dir(version)?.join("bin"))
+}
+
+pub fn tmp_dir() -> Fallible<PathBuf> {
+    Ok(notion_home()?.join("tmp"))
+}
+
+pub fn staging_lock_file() -> Fallible<PathBuf> {
+    Ok(tmp_dir()?.join("staging.lock"))
+}
+
+pub fn locks_dir() -> Fallible<PathBuf> {
+    Ok(notion_home()?.join("locks"))
+}
+
+pub fn node_install_lock_file(version: &str) -> Fallible<PathBuf> {
+    Ok(locks_dir()?.join(format!("node-{}.lock", version)))
+}
+
+pub fn yarn_install_lock_file(version: &str) -> Fallible<PathBuf> {
+    Ok(locks_dir()?.join(format!("yarn-{}.lock", version)))
+}
+
+pub fn usage_dir() -> Fallible<PathBuf> {
+    Ok(notion_home()?.join("usage"))
+}
+
+pub fn node_usage_file(version: &str) -> Fallible<PathBuf> {
+    Ok(usage_dir()?.join(format!("node-{}", version)))
+}
+
 pub fn bin_dir() -> Fallible<PathBuf> {
     Ok(notion_home()?.join("bin"))
 }
@@ -142,3 +242,7 @@ pub fn user_config_file() -> Fallible<PathBuf> {
 pub fn user_catalog_file() -> Fallible<PathBuf> {
     Ok(notion_home()?.join("catalog.toml"))
 }
+
+pub fn user_catalog_lock_file() -> Fallible<PathBuf> {
+    Ok(notion_home()?.join("catalog.toml.lock"))
+}
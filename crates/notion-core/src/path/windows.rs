@@ -23,26 +23,60 @@ cfg_if! {
     }
 }
 
+/// The key identifying this platform's Node distribution zip file in the
+/// `files` list of an entry in the public Node index.
+pub fn platform_key() -> String {
+    format!("{}-{}-zip", OS, ARCH)
+}
+
+// All three of the directories below are %NOTION_HOME% instead, if it is set.
+//
 // C:\
 //     ProgramData\
 //         Notion\
-//             cache\                                  cache_dir
-//                 node\                               node_cache_dir
-//                     node-v4.8.4-win-x64.zip         archive_file("4.8.4")
+//             cache\                                                     cache_dir
+//                 node\                                                  node_cache_dir
+//                     node-v4.8.4-win-x64.zip                            archive_file("4.8.4")
+//                     node-v4.8.4-SHASUMS256-https-nodejs.org-dist.txt   shasums_file("4.8.4", "https-nodejs.org-dist")
+//                     index-https-nodejs.org-dist.json                   node_index_file("https-nodejs.org-dist")
+//                     index-https-nodejs.org-dist.json.expires           node_index_expiry_file("https-nodejs.org-dist")
+//                     index-https-nodejs.org-dist.json.validators        node_index_validators_file("https-nodejs.org-dist")
 //                     node-v6.11.3-win-x64.zip
 //                     node-v8.6.0-win-x64.zip
 //                     ...
-//             versions\                               versions_dir
-//                 node\                               node_versions_dir
-//                     4.8.4\                          node_version_dir("4.8.4")
-//                                                     node_version_bin_dir("4.8.4")
+//                 yarn\                                                  yarn_cache_dir
+//                     yarn-v1.3.2.tar.gz                                 yarn_archive_file("1.3.2")
+//                     ...
+//             versions\                                                  versions_dir
+//                 node\                                                  node_versions_dir
+//                     4.8.4\                                             node_version_dir("4.8.4")
+//                                                                        node_version_bin_dir("4.8.4")
 //                     6.11.3\
 //                     8.6.0\
 //                     ...
-//             launchbin.exe                           launchbin_file
-//             launchscript.exe                        launchscript_file
+//                 yarn\                                                  yarn_versions_dir
+//                     1.3.2\                                             yarn_version_dir("1.3.2")
+//                         bin\                                           yarn_version_bin_dir("1.3.2")
+//                     ...
+//             tmp\                                                       tmp_dir
+//                 staging.lock                                           staging_lock_file
+//                 install-XXXXXX\                                        (staging directory for an install)
+//                 install-XXXXXX.lock                                    (held while the install is running)
+//             locks\                                                     locks_dir
+//                 node-8.6.0.lock                                        node_install_lock_file("8.6.0")
+//                 yarn-1.3.2.lock                                        yarn_install_lock_file("1.3.2")
+//                 ...
+//             usage\                                                     usage_dir
+//                 node-8.6.0                                             node_usage_file("8.6.0")
+//                 ...
+//             launchbin.exe                                              launchbin_file
+//             launchscript.exe                                           launchscript_file
 
 fn program_data_root() -> Fallible<PathBuf> {
+    if let Some(home) = super::notion_home_override() {
+        return Ok(home);
+    }
+
     #[cfg(windows)]
     return Ok(winfolder::Folder::ProgramData.path().join("Notion"));
 
@@ -58,12 +92,20 @@ pub fn node_cache_dir() -> Fallible<PathBuf> {
     Ok(cache_dir()?.join("node"))
 }
 
-pub fn node_index_file() -> Fallible<PathBuf> {
-    Ok(node_cache_dir()?.join("index.json"))
+pub fn node_index_file(mirror_key: &str) -> Fallible<PathBuf> {
+    Ok(node_cache_dir()?.join(format!("index-{}.json", mirror_key)))
+}
+
+pub fn node_index_expiry_file(mirror_key: &str) -> Fallible<PathBuf> {
+    Ok(node_cache_dir()?.join(format!("index-{}.json.expires", mirror_key)))
 }
 
-pub fn node_index_expiry_file() -> Fallible<PathBuf> {
-    Ok(node_cache_dir()?.join("index.json.expires"))
+pub fn node_index_validators_file(mirror_key: &str) -> Fallible<PathBuf> {
+    Ok(node_cache_dir()?.join(format!("index-{}.json.validators", mirror_key)))
+}
+
+pub fn yarn_cache_dir() -> Fallible<PathBuf> {
+    Ok(cache_dir()?.join("yarn"))
 }
 
 pub fn archive_extension() -> String {
@@ -86,6 +128,48 @@ pub fn node_version_bin_dir(version: &str) -> Fallible<PathBuf> {
     node_version_dir(version)
 }
 
+pub fn yarn_versions_dir() -> Fallible<PathBuf> {
+    Ok(versions_dir()?.join("yarn"))
+}
+
+pub fn yarn_version_dir(version: &str) -> Fallible<PathBuf> {
+    Ok(yarn_versions_dir()?.join(version))
+}
+
+pub fn yarn_version_bin_dir(version: &str) -> Fallible<PathBuf> {
+    Ok(yarn_version_dir(version)?.join("bin"))
+}
+
+pub fn tmp_dir() -> Fallible<PathBuf> {
+    Ok(program_data_root()?.join("tmp"))
+}
+
+pub fn staging_lock_file() -> Fallible<PathBuf> {
+    Ok(tmp_dir()?.join("staging.lock"))
+}
+
+pub fn locks_dir() -> Fallible<PathBuf> {
+    Ok(program_data_root()?.join("locks"))
+}
+
+pub fn node_install_lock_file(version: &str) -> Fallible<PathBuf> {
+    Ok(locks_dir()?.join(format!("node-{}.lock", version)))
+}
+
+pub fn yarn_install_lock_file(version: &str) -> Fallible<PathBuf> {
+    Ok(locks_dir()?.join(format!("yarn-{}.lock", version)))
+}
+
+// Usage timestamps live next to the versions they describe, rather than in
+// the per-user data directory, so that pruning accounts for every user.
+pub fn usage_dir() -> Fallible<PathBuf> {
+    Ok(program_data_root()?.join("usage"))
+}
+
+pub fn node_usage_file(version: &str) -> Fallible<PathBuf> {
+    Ok(usage_dir()?.join(format!("node-{}", version)))
+}
+
 pub fn launchbin_file() -> Fallible<PathBuf> {
     Ok(program_data_root()?.join("launchbin.exe"))
 }
@@ -100,11 +184,16 @@ pub fn launchscript_file() -> Fallible<PathBuf> {
 //             notion.exe                              notion_file
 //             shim\                                   shim_dir
 //                 node.exe                            shim_file("node")
+//                 yarn.exe                            shim_file("yarn")
 //                 npm.exe
 //                 npx.exe
 //                 ...
 
 fn program_files_root() -> Fallible<PathBuf> {
+    if let Some(home) = super::notion_home_override() {
+        return Ok(home);
+    }
+
     #[cfg(windows)]
     return Ok(winfolder::Folder::ProgramFilesX64.path().join("Notion"));
 
@@ -136,8 +225,13 @@ pub fn shim_file(toolname: &str) -> Fallible<PathBuf> {
 //                     Notion\
 //                         config.toml                 user_config_file
 //                         catalog.toml                user_catalog_file
+//                         catalog.toml.lock           user_catalog_lock_file
 
 fn local_data_root() -> Fallible<PathBuf> {
+    if let Some(home) = super::notion_home_override() {
+        return Ok(home);
+    }
+
     #[cfg(windows)]
     return Ok(winfolder::Folder::LocalAppData.path().join("Notion"));
 
@@ -152,3 +246,7 @@ pub fn user_config_file() -> Fallible<PathBuf> {
 pub fn user_catalog_file() -> Fallible<PathBuf> {
     Ok(local_data_root()?.join("catalog.toml"))
 }
+
+pub fn user_catalog_lock_file() -> Fallible<PathBuf> {
+    Ok(local_data_root()?.join("catalog.toml.lock"))
+}
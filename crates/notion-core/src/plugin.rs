@@ -1,16 +1,23 @@
 //! Types representing Notion plugins.
 
 use std::ffi::OsString;
-use std::io::Read;
+use std::fmt::Display;
+use std::io::{self, Read};
 use std::process::{Command, Stdio};
+use std::thread;
 
+use catalog::Index;
 use installer::node::Installer;
+use path;
 use serial;
 
 use cmdline_words_parser::StrExt;
-use notion_fail::{FailExt, Fallible, ResultExt};
-use semver::{Version, VersionReq};
+use notion_fail::{FailExt, Fallible, NotionFail, ResultExt};
+use reqwest;
+use semver::Version;
 use serde_json;
+use tempfile::NamedTempFile;
+use version::VersionSpec;
 
 /// A Node version resolution plugin.
 pub enum Resolve {
@@ -29,44 +36,137 @@ pub struct InvalidCommandError {
     command: String,
 }
 
+/// Thrown when a URL plugin fails to produce a valid response.
+#[derive(Fail, Debug)]
+#[fail(display = "Plugin request to {} failed: {}", url, error)]
+pub struct UrlPluginError {
+    url: String,
+    error: String,
+}
+
+impl UrlPluginError {
+    fn new<D: Display>(url: &str, error: &D) -> UrlPluginError {
+        UrlPluginError {
+            url: url.to_string(),
+            error: error.to_string(),
+        }
+    }
+}
+
+impl NotionFail for UrlPluginError {
+    fn is_user_friendly(&self) -> bool {
+        true
+    }
+    fn exit_code(&self) -> i32 {
+        6
+    }
+}
+
+/// Thrown when a bin plugin exits unsuccessfully.
+#[derive(Fail, Debug)]
+#[fail(display = "Plugin command '{}' failed ({})", command, status)]
+pub struct BinPluginError {
+    command: String,
+    status: String,
+}
+
+impl NotionFail for BinPluginError {
+    fn is_user_friendly(&self) -> bool {
+        true
+    }
+    fn exit_code(&self) -> i32 {
+        6
+    }
+}
+
+/// Parses a plugin's command-line string into a `Command`.
+fn command_for(bin: &str) -> Fallible<Command> {
+    let mut trimmed = bin.trim().to_string();
+    let mut words = trimmed.parse_cmdline_words();
+    let cmd = if let Some(word) = words.next() {
+        word
+    } else {
+        throw!(
+            InvalidCommandError {
+                command: String::from(bin.trim()),
+            }.unknown()
+        );
+    };
+    let args: Vec<OsString> = words
+        .map(|s| {
+            let mut os = OsString::new();
+            os.push(s);
+            os
+        })
+        .collect();
+    let mut command = Command::new(cmd);
+    command.args(&args);
+    Ok(command)
+}
+
 impl Resolve {
-    /// Performs resolution of a Node version based on the given semantic
-    /// versioning requirements.
-    pub fn resolve(&self, _matching: &VersionReq) -> Fallible<Installer> {
+    /// Performs resolution of a Node version based on the given version
+    /// specification.
+    pub fn resolve(&self, matching: &VersionSpec) -> Fallible<Installer> {
         match self {
-            &Resolve::Url(_) => unimplemented!(),
+            &Resolve::Url(ref url) => {
+                let request = reqwest::Url::parse_with_params(url, &[("version", matching.to_string())])
+                    .with_context(|err| UrlPluginError::new(url, err))?;
+                let response = reqwest::get(request)
+                    .with_context(|err| UrlPluginError::new(url, err))?;
+
+                if !response.status().is_success() {
+                    throw!(UrlPluginError::new(
+                        url,
+                        &format!("HTTP failure ({})", response.status())
+                    ));
+                }
+
+                let response = ResolveResponse::from_reader(response)
+                    .with_context(|_| UrlPluginError::new(url, &"invalid response"))?;
+                match response {
+                    ResolveResponse::Url { version, url } => Installer::remote(version, &url),
+                    ResolveResponse::Stream { .. } => {
+                        throw!(UrlPluginError::new(url, &"URL plugins cannot produce a stream"));
+                    }
+                }
+            }
 
             &Resolve::Bin(ref bin) => {
-                let mut trimmed = bin.trim().to_string();
-                let mut words = trimmed.parse_cmdline_words();
-                let cmd = if let Some(word) = words.next() {
-                    word
-                } else {
-                    throw!(
-                        InvalidCommandError {
-                            command: String::from(bin.trim()),
-                        }.unknown()
-                    );
-                };
-                let args: Vec<OsString> = words
-                    .map(|s| {
-                        let mut os = OsString::new();
-                        os.push(s);
-                        os
-                    })
-                    .collect();
-                let child = Command::new(cmd)
-                    .args(&args)
+                let mut child = command_for(bin)?
                     .stdin(Stdio::null())
                     .stdout(Stdio::piped())
                     .stderr(Stdio::piped())
                     .spawn()
                     .unknown()?;
-                let response = ResolveResponse::from_reader(child.stdout.unwrap())?;
+                let mut stdout = child.stdout.take().unwrap();
+                let response = ResolveResponse::from_reader(&mut stdout)?;
                 match response {
                     ResolveResponse::Url { version, url } => Installer::remote(version, &url),
-                    ResolveResponse::Stream { version: _version } => {
-                        unimplemented!("bin plugin produced a stream")
+                    ResolveResponse::Stream { version } => {
+                        // Stage the streamed archive in a temporary file so that a
+                        // partially-written stream never ends up in the cache.
+                        let cache_dir = path::node_cache_dir()?;
+                        let mut staged = NamedTempFile::new_in(&cache_dir).unknown()?;
+
+                        // Keep draining stdout while the archive streams over stderr,
+                        // so a plugin that keeps writing to stdout never blocks on a
+                        // full pipe.
+                        let drain = thread::spawn(move || io::copy(&mut stdout, &mut io::sink()));
+                        io::copy(child.stderr.as_mut().unwrap(), &mut staged).unknown()?;
+
+                        let status = child.wait().unknown()?;
+                        let _ = drain.join();
+                        if !status.success() {
+                            throw!(BinPluginError {
+                                command: String::from(bin.trim()),
+                                status: status.to_string(),
+                            });
+                        }
+
+                        let cache_file = cache_dir.join(path::archive_file(&version.to_string()));
+                        let file = staged.persist(&cache_file).unknown()?;
+                        Installer::cached(version, file, None)
                     }
                 }
             }
@@ -74,6 +174,10 @@ impl Resolve {
     }
 }
 
+#[derive(Fail, Debug)]
+#[fail(display = "Plugin produced an empty response")]
+struct EmptyResponseError;
+
 /// A response from the Node version resolution plugin.
 #[derive(Debug)]
 pub enum ResolveResponse {
@@ -89,13 +193,67 @@ pub enum ResolveResponse {
 impl ResolveResponse {
     /// Reads and parses a response from a Node version resolution plugin.
     pub fn from_reader<R: Read>(reader: R) -> Fallible<Self> {
-        let serial: serial::plugin::ResolveResponse = serde_json::from_reader(reader).unknown()?;
+        // Only read a single JSON value rather than reading to the end of the
+        // stream, since a plugin may keep its stdout open while it streams an
+        // archive over stderr.
+        let mut values = serde_json::Deserializer::from_reader(reader)
+            .into_iter::<serial::plugin::ResolveResponse>();
+        let serial = match values.next() {
+            Some(value) => value.unknown()?,
+            None => {
+                throw!(EmptyResponseError.unknown());
+            }
+        };
         Ok(serial.into_resolve_response()?)
     }
 }
 
 /// A plugin listing the available versions of Node.
 pub enum LsRemote {
+    /// Lists the available Node versions by fetching them from a URL.
     Url(String),
+
+    /// Lists the available Node versions by running an executable and
+    /// receiving the list in the process's stdout stream.
     Bin(String),
 }
+
+impl LsRemote {
+    /// Produces the index of available Node versions. Plugins respond with
+    /// the same format as the public Node server's `index.json`.
+    pub fn ls_remote(&self) -> Fallible<Index> {
+        let serial: serial::index::Index = match self {
+            &LsRemote::Url(ref url) => {
+                let response = reqwest::get(url).with_context(|err| UrlPluginError::new(url, err))?;
+
+                if !response.status().is_success() {
+                    throw!(UrlPluginError::new(
+                        url,
+                        &format!("HTTP failure ({})", response.status())
+                    ));
+                }
+
+                serde_json::from_reader(response)
+                    .with_context(|_| UrlPluginError::new(url, &"invalid response"))?
+            }
+
+            &LsRemote::Bin(ref bin) => {
+                let output = command_for(bin)?
+                    .stdin(Stdio::null())
+                    .stderr(Stdio::inherit())
+                    .output()
+                    .unknown()?;
+
+                if !output.status.success() {
+                    throw!(BinPluginError {
+                        command: String::from(bin.trim()),
+                        status: output.status.to_string(),
+                    });
+                }
+
+                serde_json::from_slice(&output.stdout).unknown()?
+            }
+        };
+        serial.into_index()
+    }
+}
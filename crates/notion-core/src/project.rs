@@ -3,11 +3,79 @@
 
 use std::env;
 use std::ffi::OsStr;
-use std::path::Path;
+use std::fmt::{self, Display, Formatter};
+use std::fs::read_to_string;
+use std::path::{Path, PathBuf};
 
-use notion_fail::{Fallible, ResultExt};
+use notion_fail::{Fallible, NotionFail, ResultExt};
 
 use manifest::Manifest;
+use serial::version::parse_nvm_requirements;
+use version::VersionSpec;
+
+/// Thrown when a `.nvmrc` or `.node-version` file does not contain a valid version.
+#[derive(Fail, Debug)]
+#[fail(display = "Invalid Node version in {}: '{}'", file, contents)]
+struct InvalidVersionFileError {
+    file: String,
+    contents: String,
+}
+
+impl NotionFail for InvalidVersionFileError {
+    fn is_user_friendly(&self) -> bool {
+        true
+    }
+    fn exit_code(&self) -> i32 {
+        8
+    }
+}
+
+/// The file from which a project's Node version was read.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum NodeSource {
+    /// The `notion.node` key of `package.json`.
+    Manifest,
+    /// An nvm-style `.nvmrc` file.
+    Nvmrc,
+    /// A `.node-version` file.
+    NodeVersion,
+}
+
+impl NodeSource {
+    fn file_name(&self) -> &'static str {
+        match self {
+            &NodeSource::Manifest => "package.json",
+            &NodeSource::Nvmrc => ".nvmrc",
+            &NodeSource::NodeVersion => ".node-version",
+        }
+    }
+}
+
+impl Display for NodeSource {
+    fn fmt(&self, f: &mut Formatter) -> Result<(), fmt::Error> {
+        f.write_str(self.file_name())
+    }
+}
+
+/// Reads the requested Node version from a `.nvmrc` or `.node-version` file
+/// in the project root, in that order of precedence.
+fn version_file(project_root: &Path) -> Fallible<Option<(VersionSpec, NodeSource)>> {
+    for &source in &[NodeSource::Nvmrc, NodeSource::NodeVersion] {
+        let file = project_root.join(source.file_name());
+        if !file.is_file() {
+            continue;
+        }
+
+        let contents = read_to_string(&file).unknown()?;
+        let spec = parse_nvm_requirements(&contents).with_context(|_| InvalidVersionFileError {
+            file: source.to_string(),
+            contents: contents.trim().to_string(),
+        })?;
+        return Ok(Some((spec, source)));
+    }
+
+    Ok(None)
+}
 
 fn is_node_root(dir: &Path) -> bool {
     dir.join("package.json").is_file()
@@ -25,38 +93,75 @@ fn is_project_root(dir: &Path) -> bool {
     is_node_root(dir) && !is_dependency(dir)
 }
 
+/// Returns the root directory of the Node project containing the current
+/// working directory, if any.
+pub fn root_for_current_dir() -> Fallible<Option<PathBuf>> {
+    let mut dir: &Path = &env::current_dir().unknown()?;
+
+    while !is_project_root(dir) {
+        dir = match dir.parent() {
+            Some(parent) => parent,
+            None => {
+                return Ok(None);
+            }
+        }
+    }
+
+    Ok(Some(dir.to_path_buf()))
+}
+
 /// A Node project tree in the filesystem.
 pub struct Project {
+    project_root: PathBuf,
     manifest: Manifest,
+    node: Option<(VersionSpec, NodeSource)>,
 }
 
 impl Project {
     /// Returns the Node project containing the current working directory,
-    /// if any.
+    /// if any. A project may request a Node version, either in the
+    /// `notion.node` key of its manifest or, failing that, in a `.nvmrc` or
+    /// `.node-version` file in its root directory.
     pub fn for_current_dir() -> Fallible<Option<Project>> {
-        let mut dir: &Path = &env::current_dir().unknown()?;
-
-        while !is_project_root(dir) {
-            dir = match dir.parent() {
-                Some(parent) => parent,
-                None => {
-                    return Ok(None);
-                }
-            }
-        }
-
-        let manifest = match Manifest::for_dir(&dir)? {
-            Some(manifest) => manifest,
+        let project_root = match root_for_current_dir()? {
+            Some(project_root) => project_root,
             None => {
                 return Ok(None);
             }
         };
 
-        Ok(Some(Project { manifest: manifest }))
+        let manifest = Manifest::for_dir(&project_root)?;
+
+        let node = match manifest.node.clone() {
+            Some(node) => Some((node, NodeSource::Manifest)),
+            None => version_file(&project_root)?,
+        };
+
+        Ok(Some(Project {
+            project_root: project_root,
+            manifest: manifest,
+            node: node,
+        }))
+    }
+
+    /// Returns the root directory of this project.
+    pub fn root(&self) -> &Path {
+        &self.project_root
     }
 
     /// Returns the project manifest (`package.json`) for this project.
     pub fn manifest(&self) -> &Manifest {
         &self.manifest
     }
+
+    /// Returns the Node version requested by this project, if any.
+    pub fn node(&self) -> Option<&VersionSpec> {
+        self.node.as_ref().map(|&(ref node, _)| node)
+    }
+
+    /// Returns the file from which this project's Node version was read, if
+    /// it requests one.
+    pub fn node_source(&self) -> Option<NodeSource> {
+        self.node.as_ref().map(|&(_, source)| source)
+    }
 }
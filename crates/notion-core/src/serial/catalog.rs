@@ -13,6 +13,8 @@ use semver::{SemVerError, Version};
 pub struct Catalog {
     #[serde(default)]
     node: NodeCatalog,
+    #[serde(default)]
+    yarn: YarnCatalog,
 }
 
 #[derive(Serialize, Deserialize)]
@@ -31,10 +33,27 @@ impl Default for NodeCatalog {
     }
 }
 
+#[derive(Serialize, Deserialize)]
+#[serde(rename = "yarn")]
+pub struct YarnCatalog {
+    activated: Option<String>,
+    versions: Vec<String>,
+}
+
+impl Default for YarnCatalog {
+    fn default() -> Self {
+        YarnCatalog {
+            activated: None,
+            versions: vec![],
+        }
+    }
+}
+
 impl Catalog {
     pub fn into_catalog(self) -> Fallible<catalog::Catalog> {
         Ok(catalog::Catalog {
             node: self.node.into_node_catalog().unknown()?,
+            yarn: self.yarn.into_yarn_catalog().unknown()?,
         })
     }
 }
@@ -58,10 +77,30 @@ impl NodeCatalog {
     }
 }
 
+impl YarnCatalog {
+    fn into_yarn_catalog(self) -> Fallible<catalog::YarnCatalog> {
+        let activated = match self.activated {
+            Some(v) => Some(Version::parse(&v[..]).unknown()?),
+            None => None,
+        };
+
+        let versions: Result<Vec<Version>, SemVerError> = self.versions
+            .into_iter()
+            .map(|s| Ok(Version::parse(&s[..])?))
+            .collect();
+
+        Ok(catalog::YarnCatalog {
+            activated: activated,
+            versions: BTreeSet::from_iter(versions.unknown()?),
+        })
+    }
+}
+
 impl catalog::Catalog {
     pub fn to_serial(&self) -> Catalog {
         Catalog {
             node: self.node.to_serial(),
+            yarn: self.yarn.to_serial(),
         }
     }
 }
@@ -73,3 +112,12 @@ impl catalog::NodeCatalog {
         }
     }
 }
+
+impl catalog::YarnCatalog {
+    fn to_serial(&self) -> YarnCatalog {
+        YarnCatalog {
+            activated: self.activated.clone().map(|v| v.to_string()),
+            versions: self.versions.iter().map(|v| v.to_string()).collect(),
+        }
+    }
+}
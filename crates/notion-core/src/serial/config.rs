@@ -7,17 +7,26 @@ use notion_fail::Fallible;
 #[derive(Serialize, Deserialize)]
 pub struct Config {
     pub node: Option<NodeConfig>,
+    pub network: Option<NetworkConfig>,
 }
 
 #[derive(Serialize, Deserialize)]
 #[serde(rename = "node")]
 pub struct NodeConfig {
+    pub mirror: Option<String>,
+
     pub resolve: Option<Plugin>,
 
     #[serde(rename = "ls-remote")]
     pub ls_remote: Option<Plugin>,
 }
 
+#[derive(Serialize, Deserialize)]
+#[serde(rename = "network")]
+pub struct NetworkConfig {
+    pub offline: Option<bool>,
+}
+
 impl Config {
     pub fn into_config(self) -> Fallible<config::Config> {
         Ok(config::Config {
@@ -26,13 +35,23 @@ impl Config {
             } else {
                 None
             },
+            network: self.network.map(|n| n.into_network_config()),
         })
     }
 }
 
+impl NetworkConfig {
+    pub fn into_network_config(self) -> config::NetworkConfig {
+        config::NetworkConfig {
+            offline: self.offline.unwrap_or(false),
+        }
+    }
+}
+
 impl NodeConfig {
     pub fn into_node_config(self) -> Fallible<config::NodeConfig> {
         Ok(config::NodeConfig {
+            mirror: self.mirror,
             resolve: if let Some(p) = self.resolve {
                 Some(p.into_resolve()?)
             } else {
@@ -1,10 +1,13 @@
 use super::super::catalog;
 
-use std::collections::{BTreeMap, HashSet};
+use std::collections::{BTreeMap, BTreeSet, HashMap, HashSet};
 use std::iter::FromIterator;
 
 use notion_fail::{Fallible, ResultExt};
 use semver::Version;
+use serde::de::IgnoredAny;
+use serde::{Deserialize, Deserializer};
+use serde_json::Value;
 
 #[derive(Serialize, Deserialize)]
 pub struct Index(Vec<Entry>);
@@ -13,6 +16,21 @@ pub struct Index(Vec<Entry>);
 pub struct Entry {
     pub version: String,
     pub files: Vec<String>,
+
+    /// The LTS codename of the release, which the public index
+    /// represents as `false` for non-LTS releases.
+    #[serde(default, deserialize_with = "lts_codename")]
+    pub lts: Option<String>,
+
+    #[serde(default)]
+    pub date: Option<String>,
+}
+
+fn lts_codename<'de, D: Deserializer<'de>>(deserializer: D) -> Result<Option<String>, D::Error> {
+    Ok(match Value::deserialize(deserializer)? {
+        Value::String(codename) => Some(codename.to_lowercase()),
+        _ => None,
+    })
 }
 
 impl Index {
@@ -21,6 +39,8 @@ impl Index {
         for entry in self.0 {
             let data = catalog::VersionData {
                 files: HashSet::from_iter(entry.files.into_iter()),
+                lts: entry.lts,
+                date: entry.date,
             };
             let mut version = &entry.version[..];
             version = version.trim();
@@ -32,3 +52,28 @@ impl Index {
         Ok(catalog::Index { entries })
     }
 }
+
+/// The abbreviated npm registry metadata for the `yarn` package, which
+/// serves as the index of available Yarn versions.
+#[derive(Deserialize)]
+pub struct YarnIndex {
+    #[serde(rename = "dist-tags", default)]
+    dist_tags: HashMap<String, String>,
+    versions: HashMap<String, IgnoredAny>,
+}
+
+impl YarnIndex {
+    pub fn into_index(self) -> Fallible<catalog::YarnIndex> {
+        let latest = match self.dist_tags.get("latest") {
+            Some(latest) => Some(Version::parse(latest).unknown()?),
+            None => None,
+        };
+
+        let mut versions = BTreeSet::new();
+        for (version, _) in self.versions {
+            versions.insert(Version::parse(&version).unknown()?);
+        }
+
+        Ok(catalog::YarnIndex { latest, versions })
+    }
+}
@@ -24,17 +24,21 @@ pub struct Manifest {
 
 #[derive(Serialize, Deserialize)]
 pub struct NotionManifest {
-    pub node: String,
+    pub node: Option<String>,
     pub yarn: Option<String>,
     // FIXME: this should be in the notion config file
     pub events_plugin: Option<String>,
 }
 
 impl Manifest {
-    pub fn into_manifest(self) -> Fallible<Option<manifest::Manifest>> {
+    pub fn into_manifest(self) -> Fallible<manifest::Manifest> {
         if let Some(notion) = self.notion {
-            return Ok(Some(manifest::Manifest {
-                node: parse_requirements(&notion.node)?,
+            return Ok(manifest::Manifest {
+                node: if let Some(node) = notion.node {
+                    Some(parse_requirements(&node)?)
+                } else {
+                    None
+                },
                 yarn: if let Some(yarn) = notion.yarn {
                     Some(parse_requirements(&yarn)?)
                 } else {
@@ -46,9 +50,14 @@ impl Manifest {
                 } else {
                     None
                 },
-            }));
+            });
         }
 
-        Ok(None)
+        Ok(manifest::Manifest {
+            node: None,
+            yarn: None,
+            dependencies: self.dependencies,
+            events_plugin: None,
+        })
     }
 }
@@ -1,14 +1,103 @@
 use notion_fail::{Fallible, ResultExt};
 use semver::VersionReq;
 
-pub fn parse_requirements(src: &str) -> Fallible<VersionReq> {
+use version::VersionSpec;
+
+/// Parses the contents of a `.nvmrc` or `.node-version` file, which can also
+/// contain nvm-style aliases (`node`, `stable`, `lts/*`) and versions with a
+/// leading `v` (e.g., `v8.9.4`).
+pub fn parse_nvm_requirements(src: &str) -> Fallible<VersionSpec> {
+    let src = src.lines().map(|line| line.trim()).find(|line| !line.is_empty()).unwrap_or("");
+
+    match &src.to_lowercase()[..] {
+        "node" | "stable" => {
+            return Ok(VersionSpec::Latest);
+        }
+        "lts/*" => {
+            return Ok(VersionSpec::Lts);
+        }
+        _ => {}
+    }
+
+    if src.starts_with('v') && src[1..].chars().next().map_or(false, |c| c.is_digit(10)) {
+        parse_requirements(&src[1..])
+    } else {
+        parse_requirements(src)
+    }
+}
+
+pub fn parse_requirements(src: &str) -> Fallible<VersionSpec> {
     let src = src.trim();
-    Ok(
+    let lower = src.to_lowercase();
+
+    if lower == "latest" {
+        return Ok(VersionSpec::Latest);
+    } else if lower == "lts" || lower == "lts/*" {
+        return Ok(VersionSpec::Lts);
+    } else if lower.starts_with("lts/") {
+        return Ok(VersionSpec::LtsCodename(lower[4..].to_string()));
+    }
+
+    Ok(VersionSpec::Semver(
         if src.len() > 0 && src.chars().next().unwrap().is_digit(10) {
             let defaulted = format!("={}", src);
             VersionReq::parse(&defaulted).unknown()?
         } else {
             VersionReq::parse(src).unknown()?
         },
-    )
+    ))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parse_requirements_aliases() {
+        assert_eq!(parse_requirements("latest").unwrap(), VersionSpec::Latest);
+        assert_eq!(parse_requirements(" Latest ").unwrap(), VersionSpec::Latest);
+        assert_eq!(parse_requirements("lts").unwrap(), VersionSpec::Lts);
+        assert_eq!(parse_requirements("lts/*").unwrap(), VersionSpec::Lts);
+        assert_eq!(
+            parse_requirements("lts/carbon").unwrap(),
+            VersionSpec::LtsCodename(String::from("carbon"))
+        );
+        assert_eq!(
+            parse_requirements("LTS/Boron").unwrap(),
+            VersionSpec::LtsCodename(String::from("boron"))
+        );
+    }
+
+    #[test]
+    fn parse_requirements_semver() {
+        assert_eq!(
+            parse_requirements("8.9.4").unwrap(),
+            VersionSpec::Semver(VersionReq::parse("=8.9.4").unwrap())
+        );
+        assert_eq!(
+            parse_requirements("^8.9").unwrap(),
+            VersionSpec::Semver(VersionReq::parse("^8.9").unwrap())
+        );
+        assert!(parse_requirements("not a version").is_err());
+    }
+
+    #[test]
+    fn parse_nvm_requirements_aliases() {
+        assert_eq!(parse_nvm_requirements("node").unwrap(), VersionSpec::Latest);
+        assert_eq!(parse_nvm_requirements("stable").unwrap(), VersionSpec::Latest);
+        assert_eq!(parse_nvm_requirements("lts/*").unwrap(), VersionSpec::Lts);
+        assert_eq!(
+            parse_nvm_requirements("lts/carbon").unwrap(),
+            VersionSpec::LtsCodename(String::from("carbon"))
+        );
+    }
+
+    #[test]
+    fn parse_nvm_requirements_versions() {
+        let exact = VersionSpec::Semver(VersionReq::parse("=8.9.4").unwrap());
+        assert_eq!(parse_nvm_requirements("v8.9.4").unwrap(), exact);
+        assert_eq!(parse_nvm_requirements("8.9.4").unwrap(), exact);
+        assert_eq!(parse_nvm_requirements("\n  v8.9.4  \n\n").unwrap(), exact);
+        assert_eq!(parse_nvm_requirements("8.9.4\r\nignored\r\n").unwrap(), exact);
+    }
 }
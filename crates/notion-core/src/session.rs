@@ -5,13 +5,16 @@
 use catalog::{Catalog, LazyCatalog};
 use config::{Config, LazyConfig};
 use installer::Installed;
-use project::Project;
+use manifest::Manifest;
+use project::{self, Project};
 use std::fmt::{self, Display, Formatter};
+use std::path::Path;
 use std::process::exit;
 
 use event::EventLog;
-use notion_fail::{Fallible, NotionError};
-use semver::{Version, VersionReq};
+use notion_fail::{Fallible, NotionError, NotionFail};
+use semver::Version;
+use version::VersionSpec;
 
 #[derive(Eq, PartialEq, Ord, PartialOrd, Clone, Copy)]
 pub enum ActivityKind {
@@ -19,7 +22,15 @@ pub enum ActivityKind {
     Uninstall,
     Current,
     Use,
+    List,
+    LsRemote,
+    Cache,
+    Prune,
+    Shim,
     Node,
+    Yarn,
+    Binary,
+    Script,
     Notion,
     Tool,
     Help,
@@ -33,7 +44,15 @@ impl Display for ActivityKind {
             &ActivityKind::Uninstall => "uninstall",
             &ActivityKind::Current => "current",
             &ActivityKind::Use => "use",
+            &ActivityKind::List => "list",
+            &ActivityKind::LsRemote => "ls-remote",
+            &ActivityKind::Cache => "cache",
+            &ActivityKind::Prune => "prune",
+            &ActivityKind::Shim => "shim",
             &ActivityKind::Node => "node",
+            &ActivityKind::Yarn => "yarn",
+            &ActivityKind::Binary => "binary",
+            &ActivityKind::Script => "script",
             &ActivityKind::Notion => "notion",
             &ActivityKind::Tool => "tool",
             &ActivityKind::Help => "help",
@@ -43,6 +62,20 @@ impl Display for ActivityKind {
     }
 }
 
+/// Thrown when a command requires a Node project but none was found.
+#[derive(Fail, Debug)]
+#[fail(display = "Not in a Node project (no package.json found)")]
+struct NoProjectError;
+
+impl NotionFail for NoProjectError {
+    fn is_user_friendly(&self) -> bool {
+        true
+    }
+    fn exit_code(&self) -> i32 {
+        7
+    }
+}
+
 /// Represents the user's state during an execution of a Notion tool. The session
 /// encapsulates a number of aspects of the environment in which the tool was
 /// invoked, including:
@@ -89,51 +122,132 @@ impl Session {
     }
 
     /// Produces the version of Node for the current session. If there is an
-    /// active project with Notion settings, this will ensure a compatible
-    /// version of Node is installed before returning. If there is no active
-    /// project with Notion settings, this produces the global version, which
-    /// may be `None`.
+    /// active project that requests a version of Node, this will ensure a
+    /// compatible version of Node is installed before returning. Otherwise,
+    /// this produces the global version, which may be `None`.
     pub fn current_node(&mut self) -> Fallible<Option<Version>> {
         if let Some(ref project) = self.project {
-            let requirements = &project.manifest().node;
-            let catalog = self.catalog.get_mut()?;
-            let available = catalog.node.resolve_local(&requirements);
+            if let Some(requirements) = project.node() {
+                let catalog = self.catalog.get_mut()?;
+                let config = self.config.get()?;
+                let available = catalog.node.resolve_local(requirements, config);
 
-            if available.is_some() {
-                return Ok(available);
-            }
+                if available.is_some() {
+                    return Ok(available);
+                }
 
-            let config = self.config.get()?;
-            let installed = catalog.install_node(&requirements, config)?;
+                let installed = catalog.install_node(requirements, config)?;
 
-            return Ok(Some(installed.into_version()));
+                return Ok(Some(installed.into_version()));
+            }
         }
 
         Ok(self.catalog()?.node.activated.clone())
     }
 
-    /// Installs a version of Node matching the specified semantic verisoning
-    /// requirements.
-    pub fn install_node(&mut self, matching: &VersionReq) -> Fallible<Installed> {
+    /// Installs a version of Node matching the specified version specification.
+    pub fn install_node(&mut self, matching: &VersionSpec) -> Fallible<Installed> {
         let catalog = self.catalog.get_mut()?;
         let config = self.config.get()?;
         catalog.install_node(matching, config)
     }
 
-    /// Activates a version of Node matching the specified semantic versioning
-    /// requirements.
-    pub fn activate_node(&mut self, matching: &VersionReq) -> Fallible<()> {
+    /// Installs Node from an archive file. The version is determined from the
+    /// archive unless it is specified.
+    pub fn install_node_from_file(
+        &mut self,
+        archive: &Path,
+        version: Option<Version>,
+    ) -> Fallible<Installed> {
+        let catalog = self.catalog.get_mut()?;
+        let config = self.config.get()?;
+        catalog.install_node_from_file(archive, version, config)
+    }
+
+    /// Activates a version of Node matching the specified version specification.
+    pub fn activate_node(&mut self, matching: &VersionSpec) -> Fallible<()> {
         let catalog = self.catalog.get_mut()?;
         let config = self.config.get()?;
         catalog.activate_node(matching, config)
     }
 
+    /// Installs a version of Node matching the specified version specification
+    /// and pins it in the current project's manifest.
+    pub fn pin_node(&mut self, matching: &VersionSpec) -> Fallible<Version> {
+        let project_root = match project::root_for_current_dir()? {
+            Some(project_root) => project_root,
+            None => {
+                throw!(NoProjectError);
+            }
+        };
+
+        let version = self.install_node(matching)?.into_version();
+        Manifest::update_node(&project_root, &version.to_string())?;
+        Ok(version)
+    }
+
+    /// Produces the version of Yarn for the current session. If there is an
+    /// active project that requests a version of Yarn, this will ensure a
+    /// compatible version of Yarn is installed before returning. Otherwise,
+    /// this produces the global version, which may be `None`.
+    pub fn current_yarn(&mut self) -> Fallible<Option<Version>> {
+        if let Some(ref project) = self.project {
+            if let Some(ref requirements) = project.manifest().yarn {
+                let catalog = self.catalog.get_mut()?;
+                let config = self.config.get()?;
+                let available = catalog.yarn.resolve_local(&requirements);
+
+                if available.is_some() {
+                    return Ok(available);
+                }
+
+                let installed = catalog.install_yarn(&requirements, config)?;
+
+                return Ok(Some(installed.into_version()));
+            }
+        }
+
+        Ok(self.catalog()?.yarn.activated.clone())
+    }
+
+    /// Installs a version of Yarn matching the specified version specification.
+    pub fn install_yarn(&mut self, matching: &VersionSpec) -> Fallible<Installed> {
+        let catalog = self.catalog.get_mut()?;
+        let config = self.config.get()?;
+        catalog.install_yarn(matching, config)
+    }
+
+    /// Activates a version of Yarn matching the specified version specification.
+    pub fn activate_yarn(&mut self, matching: &VersionSpec) -> Fallible<()> {
+        let catalog = self.catalog.get_mut()?;
+        let config = self.config.get()?;
+        catalog.activate_yarn(matching, config)
+    }
+
+    /// Installs a version of Yarn matching the specified version specification
+    /// and pins it in the current project's manifest.
+    pub fn pin_yarn(&mut self, matching: &VersionSpec) -> Fallible<Version> {
+        let project_root = match project::root_for_current_dir()? {
+            Some(project_root) => project_root,
+            None => {
+                throw!(NoProjectError);
+            }
+        };
+
+        let version = self.install_yarn(matching)?.into_version();
+        Manifest::update_yarn(&project_root, &version.to_string())?;
+        Ok(version)
+    }
+
     pub fn add_event_start(&mut self, activity_kind: ActivityKind) {
         self.event_log.add_event_start(activity_kind)
     }
     pub fn add_event_end(&mut self, activity_kind: ActivityKind, exit_code: i32) {
         self.event_log.add_event_end(activity_kind, exit_code)
     }
+    pub fn add_event_signal_end(&mut self, activity_kind: ActivityKind, exit_code: i32, signal: i32) {
+        self.event_log.add_event_signal_end(activity_kind, exit_code, signal)
+    }
     pub fn add_event_error(&mut self, activity_kind: ActivityKind, error: &NotionError) {
         self.event_log.add_event_error(activity_kind, error)
     }
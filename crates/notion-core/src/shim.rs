@@ -0,0 +1,296 @@
+//! Provides functions for creating, listing and removing the shims in the
+//! Notion shim directory.
+
+use std::ffi::OsStr;
+use std::fmt::{self, Display, Formatter};
+use std::fs::{self, create_dir_all, read_dir, remove_file};
+use std::path::{Path, PathBuf};
+
+use notion_fail::{Fallible, NotionFail, ResultExt};
+use path;
+
+/// Thrown when creating a shim that already exists.
+#[derive(Fail, Debug)]
+#[fail(display = "Shim '{}' already exists", name)]
+struct ShimExistsError {
+    name: String,
+}
+
+impl NotionFail for ShimExistsError {
+    fn is_user_friendly(&self) -> bool {
+        true
+    }
+    fn exit_code(&self) -> i32 {
+        11
+    }
+}
+
+/// Thrown when removing a shim that doesn't exist.
+#[derive(Fail, Debug)]
+#[fail(display = "No shim named '{}'", name)]
+struct NoSuchShimError {
+    name: String,
+}
+
+impl NotionFail for NoSuchShimError {
+    fn is_user_friendly(&self) -> bool {
+        true
+    }
+    fn exit_code(&self) -> i32 {
+        11
+    }
+}
+
+/// Thrown when a shim name isn't a plain file name.
+#[derive(Fail, Debug)]
+#[fail(display = "Invalid shim name: '{}'", name)]
+struct InvalidShimNameError {
+    name: String,
+}
+
+impl NotionFail for InvalidShimNameError {
+    fn is_user_friendly(&self) -> bool {
+        true
+    }
+    fn exit_code(&self) -> i32 {
+        11
+    }
+}
+
+/// Thrown when removing one of Notion's own tool shims (such as `node`).
+#[derive(Fail, Debug)]
+#[fail(display = "Shim '{}' belongs to Notion and can't be removed", name)]
+struct ToolShimError {
+    name: String,
+}
+
+impl NotionFail for ToolShimError {
+    fn is_user_friendly(&self) -> bool {
+        true
+    }
+    fn exit_code(&self) -> i32 {
+        11
+    }
+}
+
+/// The kind of executable a shim launches.
+#[derive(Eq, PartialEq, Clone, Copy, Debug)]
+pub enum ShimKind {
+    /// A shim to a binary executable, by way of `launchbin`.
+    Binary,
+    /// A shim to a script (such as `npm`), by way of `launchscript`.
+    Script,
+    /// A dedicated shim (such as the `node` and `yarn` shims).
+    Tool,
+}
+
+impl Display for ShimKind {
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        f.write_str(match *self {
+            ShimKind::Binary => "binary",
+            ShimKind::Script => "script",
+            ShimKind::Tool => "tool",
+        })
+    }
+}
+
+/// A shim in the shim directory.
+pub struct Shim {
+    pub name: String,
+    pub kind: ShimKind,
+    /// The launcher the shim points to, if it is missing (which breaks the shim).
+    pub missing_target: Option<PathBuf>,
+}
+
+impl Shim {
+    /// Tests whether the shim's target is missing.
+    pub fn is_broken(&self) -> bool {
+        self.missing_target.is_some()
+    }
+}
+
+/// Creates a shim that launches the named executable of the Node version in
+/// use, either as a binary (by way of `launchbin`) or as a script (by way of
+/// `launchscript`).
+pub fn create(name: &str, script: bool) -> Fallible<()> {
+    let shim_file = shim_file(name)?;
+    if fs::symlink_metadata(&shim_file).is_ok() {
+        throw!(ShimExistsError {
+            name: name.to_string(),
+        });
+    }
+
+    let launcher = if script {
+        path::launchscript_file()?
+    } else {
+        path::launchbin_file()?
+    };
+
+    create_dir_all(path::shim_dir()?).unknown()?;
+    link(&launcher, &shim_file)
+}
+
+/// Removes the named shim. Notion's own tool shims can't be removed.
+pub fn remove(name: &str) -> Fallible<()> {
+    let shim_file = shim_file(name)?;
+    if fs::symlink_metadata(&shim_file).is_err() {
+        throw!(NoSuchShimError {
+            name: name.to_string(),
+        });
+    }
+    if inspect(name.to_string(), &shim_file)?.kind == ShimKind::Tool {
+        throw!(ToolShimError {
+            name: name.to_string(),
+        });
+    }
+    remove_file(&shim_file).unknown()
+}
+
+/// Produces the path of the named shim. The name must be a plain file name,
+/// so that no file outside the shim directory is ever created or removed.
+fn shim_file(name: &str) -> Fallible<PathBuf> {
+    if !is_valid_name(name) {
+        throw!(InvalidShimNameError {
+            name: name.to_string(),
+        });
+    }
+    path::shim_file(name)
+}
+
+/// Tests whether a shim name is a plain file name (not empty, `.` or `..`,
+/// and without any directory components).
+fn is_valid_name(name: &str) -> bool {
+    Path::new(name).file_name() == Some(OsStr::new(name))
+}
+
+/// Produces all the shims in the shim directory, sorted by name.
+pub fn list() -> Fallible<Vec<Shim>> {
+    let shim_dir = path::shim_dir()?;
+    if !shim_dir.is_dir() {
+        return Ok(Vec::new());
+    }
+
+    let mut shims = Vec::new();
+    for entry in read_dir(&shim_dir).unknown()? {
+        let entry = entry.unknown()?;
+        let file_name = entry.file_name().to_string_lossy().into_owned();
+        if let Some(name) = shim_name(&file_name) {
+            shims.push(inspect(name, &entry.path())?);
+        }
+    }
+
+    shims.sort_by(|a, b| a.name.cmp(&b.name));
+    Ok(shims)
+}
+
+cfg_if! {
+    if #[cfg(unix)] {
+        use std::os::unix::fs::symlink;
+
+        // On Unix, shims are symlinks to the launchers.
+
+        fn link(launcher: &Path, shim_file: &Path) -> Fallible<()> {
+            symlink(launcher, shim_file).unknown()
+        }
+
+        fn shim_name(file_name: &str) -> Option<String> {
+            Some(file_name.to_string())
+        }
+
+        fn inspect(name: String, shim_file: &Path) -> Fallible<Shim> {
+            if !fs::symlink_metadata(shim_file).unknown()?.file_type().is_symlink() {
+                return Ok(Shim {
+                    name,
+                    kind: ShimKind::Tool,
+                    missing_target: None,
+                });
+            }
+
+            let target = fs::read_link(shim_file).unknown()?;
+            let kind = if target == path::launchbin_file()? {
+                ShimKind::Binary
+            } else if target == path::launchscript_file()? {
+                ShimKind::Script
+            } else {
+                ShimKind::Tool
+            };
+
+            // A symlink's metadata can only be read through it if its target exists.
+            let missing_target = if fs::metadata(shim_file).is_err() {
+                Some(target)
+            } else {
+                None
+            };
+
+            Ok(Shim {
+                name,
+                kind,
+                missing_target,
+            })
+        }
+    } else {
+        // On Windows, shims are copies of the launchers, so they can't be
+        // broken by a missing launcher. A shim's kind is determined by
+        // which launcher it is a copy of.
+
+        fn link(launcher: &Path, shim_file: &Path) -> Fallible<()> {
+            fs::copy(launcher, shim_file).unknown()?;
+            Ok(())
+        }
+
+        fn shim_name(file_name: &str) -> Option<String> {
+            if file_name.ends_with(".exe") {
+                Some(file_name[..file_name.len() - ".exe".len()].to_string())
+            } else {
+                None
+            }
+        }
+
+        fn inspect(name: String, shim_file: &Path) -> Fallible<Shim> {
+            let contents = fs::read(shim_file).unknown()?;
+            let kind = if same_contents(&contents, &path::launchbin_file()?)? {
+                ShimKind::Binary
+            } else if same_contents(&contents, &path::launchscript_file()?)? {
+                ShimKind::Script
+            } else {
+                ShimKind::Tool
+            };
+
+            Ok(Shim {
+                name,
+                kind,
+                missing_target: None,
+            })
+        }
+
+        fn same_contents(contents: &[u8], file: &Path) -> Fallible<bool> {
+            if !file.is_file() {
+                return Ok(false);
+            }
+            Ok(fs::read(file).unknown()? == contents)
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn valid_names() {
+        assert!(is_valid_name("tsc"));
+        assert!(is_valid_name("ember-cli"));
+        assert!(is_valid_name(".bin"));
+    }
+
+    #[test]
+    fn invalid_names() {
+        assert!(!is_valid_name(""));
+        assert!(!is_valid_name("."));
+        assert!(!is_valid_name(".."));
+        assert!(!is_valid_name("../tsc"));
+        assert!(!is_valid_name("bin/tsc"));
+        assert!(!is_valid_name("/usr/bin/tsc"));
+        assert!(!is_valid_name("tsc/"));
+    }
+}
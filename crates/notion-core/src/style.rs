@@ -19,6 +19,11 @@ pub fn display_error_prefix() {
     eprint!("{} ", style("error:").red().bold());
 }
 
+/// Displays a warning to stderr with a styled `"warning:"` prefix.
+pub fn display_warning<W: Display>(warning: &W) {
+    eprintln!("{} {}", style("warning:").yellow().bold(), warning);
+}
+
 /// Displays a generic message for internal errors to stderr.
 pub fn display_unknown_error<E: Fail>(err: &E) {
     display_error_prefix();
@@ -56,6 +61,24 @@ pub fn display_unknown_error<E: Fail>(err: &E) {
     }
 }
 
+/// Formats a number of bytes as a human-readable size (e.g., `"12.3 MB"`).
+pub fn human_size(bytes: u64) -> String {
+    const UNITS: [&'static str; 5] = ["B", "KB", "MB", "GB", "TB"];
+
+    let mut size = bytes as f64;
+    let mut unit = 0;
+    while size >= 1024.0 && unit < UNITS.len() - 1 {
+        size /= 1024.0;
+        unit += 1;
+    }
+
+    if unit == 0 {
+        format!("{} {}", bytes, UNITS[unit])
+    } else {
+        format!("{:.1} {}", size, UNITS[unit])
+    }
+}
+
 #[derive(Eq, PartialEq, Ord, PartialOrd, Clone, Copy)]
 pub enum Action {
     Installing,
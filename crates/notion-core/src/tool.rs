@@ -2,12 +2,24 @@
 
 use std::env::{args_os, ArgsOs};
 use std::ffi::{OsStr, OsString};
+use std::io;
 use std::marker::Sized;
-use std::path::Path;
-use std::process::{exit, Command};
+use std::path::{Path, PathBuf};
+use std::process::{exit, Command, ExitStatus};
 
+#[cfg(unix)]
+use std::os::unix::process::{CommandExt, ExitStatusExt};
+#[cfg(unix)]
+use std::sync::atomic::{AtomicUsize, Ordering};
+
+#[cfg(unix)]
+use libc;
+
+use catalog::record_node_use;
 use env;
 use notion_fail::{FailExt, Fallible, NotionError, NotionFail, ResultExt};
+use path;
+use semver::Version;
 use session::{ActivityKind, Session};
 use style;
 
@@ -56,14 +68,34 @@ pub trait Tool: Sized {
     /// Delegates the current process to this tool.
     fn exec(self, mut session: Session) -> ! {
         let mut command = self.command();
-        let status = command.status().unknown();
+
+        // Only an events plugin needs the tool's exit status, so without one,
+        // on Unix, the shim replaces itself with the tool rather than staying
+        // around as its parent process.
+        #[cfg(unix)]
+        {
+            if session.events_command().is_none() {
+                let err = command.exec().unknown();
+                style::display_error(&err);
+                session.add_event_error(ActivityKind::Tool, &err);
+                session.exit(1);
+            }
+        }
+
+        let status = run(&mut command).unknown();
         match status {
             Ok(status) if status.success() => {
                 session.add_event_end(ActivityKind::Tool, 0);
                 session.exit(0);
             }
             Ok(status) => {
-                // ISSUE (#36): if None, in unix, find out the signal
+                if let Some(signal) = exit_signal(&status) {
+                    // Exit the way shells report a process killed by a signal.
+                    let code = 128 + signal;
+                    session.add_event_signal_end(ActivityKind::Tool, code, signal);
+                    session.exit(code);
+                }
+
                 let code = status.code().unwrap_or(1);
                 session.add_event_end(ActivityKind::Tool, code);
                 session.exit(code);
@@ -78,6 +110,112 @@ pub trait Tool: Sized {
     }
 }
 
+/// The signals that a shim forwards to the tool it delegates to.
+#[cfg(unix)]
+const FORWARDED_SIGNALS: [libc::c_int; 4] =
+    [libc::SIGTERM, libc::SIGHUP, libc::SIGINT, libc::SIGQUIT];
+
+/// The signals that a terminal sends (e.g., Ctrl-C) to its whole foreground
+/// process group.
+#[cfg(unix)]
+const TERMINAL_SIGNALS: [libc::c_int; 2] = [libc::SIGINT, libc::SIGQUIT];
+
+/// The process ID of the running tool, or `0` if there is none.
+#[cfg(unix)]
+static CHILD_PID: AtomicUsize = AtomicUsize::new(0);
+
+#[cfg(unix)]
+extern "C" fn forward_signal(signal: libc::c_int) {
+    let pid = CHILD_PID.load(Ordering::SeqCst);
+    if pid == 0 {
+        return;
+    }
+
+    // When the shim is in the terminal's foreground process group, the tool
+    // receives terminal signals directly, and forwarding them would deliver
+    // them twice.
+    if TERMINAL_SIGNALS.contains(&signal) && in_foreground() {
+        return;
+    }
+
+    unsafe {
+        libc::kill(pid as libc::pid_t, signal);
+    }
+}
+
+/// Tests whether this process is in the foreground process group of the
+/// terminal attached to any of its standard streams.
+#[cfg(unix)]
+fn in_foreground() -> bool {
+    let group = unsafe { libc::getpgrp() };
+    [libc::STDIN_FILENO, libc::STDOUT_FILENO, libc::STDERR_FILENO]
+        .iter()
+        .any(|&fd| unsafe { libc::tcgetpgrp(fd) } == group)
+}
+
+/// Runs a command to completion, forwarding the signals sent to the shim
+/// (such as `SIGTERM` from a supervisor or `SIGINT` from `kill -INT`) to it,
+/// and produces its exit status. Signals that the shim was started with
+/// ignored (e.g., `SIGHUP` under `nohup`) stay ignored, by the tool too. The
+/// shim's signal dispositions are restored afterwards.
+#[cfg(unix)]
+fn run(command: &mut Command) -> io::Result<ExitStatus> {
+    let mut previous = Vec::new();
+    for &signal in FORWARDED_SIGNALS.iter() {
+        let handler = forward_signal as extern "C" fn(libc::c_int) as libc::sighandler_t;
+        let disposition = unsafe { libc::signal(signal, handler) };
+        if disposition == libc::SIG_IGN {
+            unsafe {
+                libc::signal(signal, libc::SIG_IGN);
+            }
+        }
+        previous.push((signal, disposition));
+    }
+
+    // Handled signals are reset to their defaults in the child when it execs,
+    // so restore the dispositions the shim inherited (ignored ones, notably)
+    // for the child.
+    let inherited = previous.clone();
+    command.before_exec(move || {
+        for &(signal, disposition) in inherited.iter() {
+            unsafe {
+                libc::signal(signal, disposition);
+            }
+        }
+        Ok(())
+    });
+
+    let status = command.spawn().and_then(|mut child| {
+        CHILD_PID.store(child.id() as usize, Ordering::SeqCst);
+        child.wait()
+    });
+    CHILD_PID.store(0, Ordering::SeqCst);
+
+    for (signal, disposition) in previous {
+        unsafe {
+            libc::signal(signal, disposition);
+        }
+    }
+
+    status
+}
+
+#[cfg(windows)]
+fn run(command: &mut Command) -> io::Result<ExitStatus> {
+    command.status()
+}
+
+/// Produces the signal that terminated a process, if any.
+#[cfg(unix)]
+fn exit_signal(status: &ExitStatus) -> Option<i32> {
+    status.signal()
+}
+
+#[cfg(windows)]
+fn exit_signal(_status: &ExitStatus) -> Option<i32> {
+    None
+}
+
 /// Represents a delegated script.
 pub struct Script(Command);
 
@@ -87,10 +225,24 @@ pub struct Binary(Command);
 /// Represents a Node executable.
 pub struct Node(Command);
 
+/// Represents a Yarn executable.
+pub struct Yarn(Command);
+
 #[cfg(windows)]
 impl Tool for Script {
     fn new(session: &mut Session) -> Fallible<Self> {
-        unimplemented!()
+        session.add_event_start(ActivityKind::Script);
+
+        // Node installs scripts like npm as `.cmd` files next to `node.exe`.
+        let mut args = args_os();
+        let exe = arg0(&mut args)?;
+        let mut script = Path::new(&exe)
+            .file_stem()
+            .map(|stem| stem.to_os_string())
+            .unwrap_or(exe.clone());
+        script.push(".cmd");
+        let (file, path_var) = node_tool(session, &script)?;
+        Ok(Self::from_components(file.as_os_str(), args, &path_var))
     }
 
     fn from_components(exe: &OsStr, args: ArgsOs, path_var: &OsStr) -> Self {
@@ -122,8 +274,13 @@ fn command_for(exe: &OsStr, args: ArgsOs, path_var: &OsStr) -> Command {
 
 #[cfg(unix)]
 impl Tool for Script {
-    fn new(_session: &mut Session) -> Fallible<Self> {
-        unimplemented!()
+    fn new(session: &mut Session) -> Fallible<Self> {
+        session.add_event_start(ActivityKind::Script);
+
+        let mut args = args_os();
+        let exe = arg0(&mut args)?;
+        let (file, path_var) = node_tool(session, &exe)?;
+        Ok(Self::from_components(file.as_os_str(), args, &path_var))
     }
 
     fn from_components(exe: &OsStr, args: ArgsOs, path_var: &OsStr) -> Self {
@@ -136,8 +293,13 @@ impl Tool for Script {
 }
 
 impl Tool for Binary {
-    fn new(_session: &mut Session) -> Fallible<Self> {
-        unimplemented!()
+    fn new(session: &mut Session) -> Fallible<Self> {
+        session.add_event_start(ActivityKind::Binary);
+
+        let mut args = args_os();
+        let exe = arg0(&mut args)?;
+        let (file, path_var) = node_tool(session, &exe)?;
+        Ok(Self::from_components(file.as_os_str(), args, &path_var))
     }
 
     fn from_components(exe: &OsStr, args: ArgsOs, path_var: &OsStr) -> Self {
@@ -179,6 +341,48 @@ impl NotionFail for NoGlobalError {
     }
 }
 
+/// Thrown when the Node version in use doesn't provide a tool that a shim delegates to.
+#[derive(Fail, Debug)]
+#[fail(display = "{} is not available in Node v{}", tool, version)]
+struct NoSuchToolError {
+    tool: String,
+    version: Version,
+}
+
+impl NotionFail for NoSuchToolError {
+    fn is_user_friendly(&self) -> bool {
+        true
+    }
+    fn exit_code(&self) -> i32 {
+        127
+    }
+}
+
+/// Resolves the session's Node version and finds the named executable (such
+/// as `npm` or the bin of a globally installed package) in that version's
+/// bin directory. Produces the path of the executable and the `PATH` to run
+/// it with.
+fn node_tool(session: &mut Session, file_name: &OsStr) -> Fallible<(PathBuf, OsString)> {
+    let version = if let Some(version) = session.current_node()? {
+        version
+    } else {
+        throw!(NoGlobalError);
+    };
+    // Failing to record the use shouldn't keep the tool from running.
+    let _ = record_node_use(&version);
+
+    let version_string = version.to_string();
+    let file = path::node_version_bin_dir(&version_string)?.join(file_name);
+    if !file.is_file() {
+        throw!(NoSuchToolError {
+            tool: file_name.to_string_lossy().to_string(),
+            version: version,
+        });
+    }
+
+    Ok((file, env::path_for(&version_string)))
+}
+
 impl Tool for Node {
     fn new(session: &mut Session) -> Fallible<Self> {
         session.add_event_start(ActivityKind::Node);
@@ -190,6 +394,8 @@ impl Tool for Node {
         } else {
             throw!(NoGlobalError.unknown());
         };
+        // Failing to record the use shouldn't keep Node from running.
+        let _ = record_node_use(&version);
         let path_var = env::path_for(&version.to_string());
         Ok(Self::from_components(&exe, args, &path_var))
     }
@@ -202,3 +408,46 @@ impl Tool for Node {
         self.0
     }
 }
+
+#[derive(Fail, Debug)]
+#[fail(display = "No Yarn version selected")]
+struct NoGlobalYarnError;
+
+impl NotionFail for NoGlobalYarnError {
+    fn is_user_friendly(&self) -> bool {
+        true
+    }
+    fn exit_code(&self) -> i32 {
+        2
+    }
+}
+
+impl Tool for Yarn {
+    fn new(session: &mut Session) -> Fallible<Self> {
+        session.add_event_start(ActivityKind::Yarn);
+
+        let mut args = args_os();
+        let exe = arg0(&mut args)?;
+        let yarn_version = if let Some(version) = session.current_yarn()? {
+            version
+        } else {
+            throw!(NoGlobalYarnError);
+        };
+        let node_version = if let Some(version) = session.current_node()? {
+            version
+        } else {
+            throw!(NoGlobalError);
+        };
+        let _ = record_node_use(&node_version);
+        let path_var = env::path_for_yarn(&node_version.to_string(), &yarn_version.to_string());
+        Ok(Self::from_components(&exe, args, &path_var))
+    }
+
+    fn from_components(exe: &OsStr, args: ArgsOs, path_var: &OsStr) -> Self {
+        Yarn(command_for(exe, args, path_var))
+    }
+
+    fn command(self) -> Command {
+        self.0
+    }
+}
@@ -0,0 +1,57 @@
+//! Provides the `VersionSpec` type, which represents a requested tool version.
+
+use std::fmt::{self, Display, Formatter};
+
+use semver::{Version, VersionReq};
+
+/// A requested tool version, which is either a set of semantic versioning
+/// requirements or an alias that is resolved against the public Node index.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum VersionSpec {
+    /// Semantic versioning requirements (e.g., `^8.9`).
+    Semver(VersionReq),
+
+    /// The latest available version (`latest`).
+    Latest,
+
+    /// The latest available LTS version (`lts`).
+    Lts,
+
+    /// The latest available version of a named LTS release line (e.g., `lts/carbon`).
+    LtsCodename(String),
+}
+
+impl VersionSpec {
+    /// Tests whether the specified version satisfies this specification. The
+    /// `lts` argument is the LTS codename of the version, if it is an LTS release.
+    pub fn matches(&self, version: &Version, lts: Option<&str>) -> bool {
+        match self {
+            &VersionSpec::Semver(ref req) => req.matches(version),
+            &VersionSpec::Latest => true,
+            &VersionSpec::Lts => lts.is_some(),
+            &VersionSpec::LtsCodename(ref codename) => {
+                lts.map_or(false, |lts| lts.eq_ignore_ascii_case(codename))
+            }
+        }
+    }
+
+    /// Indicates whether matching this specification requires the LTS
+    /// information from the public Node index.
+    pub fn is_lts(&self) -> bool {
+        match self {
+            &VersionSpec::Lts | &VersionSpec::LtsCodename(_) => true,
+            _ => false,
+        }
+    }
+}
+
+impl Display for VersionSpec {
+    fn fmt(&self, f: &mut Formatter) -> Result<(), fmt::Error> {
+        match self {
+            &VersionSpec::Semver(ref req) => write!(f, "{}", req),
+            &VersionSpec::Latest => f.write_str("latest"),
+            &VersionSpec::Lts => f.write_str("lts"),
+            &VersionSpec::LtsCodename(ref codename) => write!(f, "lts/{}", codename),
+        }
+    }
+}
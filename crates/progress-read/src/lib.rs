@@ -38,6 +38,11 @@ impl<R: Read, T, F: FnMut(&T, usize) -> T> ProgressRead<R, T, F> {
             progress,
         }
     }
+
+    /// Consumes this progress reader, returning the underlying reader.
+    pub fn into_inner(self) -> R {
+        self.source
+    }
 }
 
 impl<R: Read + Seek, T, F: FnMut(&T, usize) -> T> Seek for ProgressRead<R, T, F> {
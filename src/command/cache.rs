@@ -0,0 +1,159 @@
+use notion_core::cache::{self, Archive, Verified};
+use notion_core::session::{ActivityKind, Session};
+use notion_core::style::human_size;
+use notion_fail::Fallible;
+
+use Notion;
+use command::{Command, CommandName, Help};
+
+#[derive(Debug, Deserialize)]
+pub(crate) struct Args {
+    cmd_list: bool,
+    cmd_clean: bool,
+    cmd_verify: bool,
+    flag_all: bool,
+}
+
+pub(crate) enum Cache {
+    Help,
+    List,
+    Clean { all: bool },
+    Verify,
+}
+
+impl Command for Cache {
+    type Args = Args;
+
+    const USAGE: &'static str = "
+Inspect and clean the cache of downloaded archives
+
+Usage:
+    notion cache list
+    notion cache clean [--all | --unused]
+    notion cache verify
+    notion cache -h | --help
+
+Commands:
+    list           List the cached archives and their sizes
+    clean          Remove cached archives
+    verify         Check the cached archives against their published checksums
+
+Options:
+    -h, --help     Display this message
+    --all          Remove all the cached archives
+    --unused       Remove the archives of versions that are not installed (the default)
+";
+
+    fn help() -> Self {
+        Cache::Help
+    }
+
+    fn parse(
+        _: Notion,
+        Args {
+            cmd_list,
+            cmd_clean,
+            cmd_verify,
+            flag_all,
+        }: Args,
+    ) -> Fallible<Self> {
+        Ok(if cmd_list {
+            Cache::List
+        } else if cmd_clean {
+            Cache::Clean { all: flag_all }
+        } else if cmd_verify {
+            Cache::Verify
+        } else {
+            Cache::Help
+        })
+    }
+
+    fn run(self, session: &mut Session) -> Fallible<bool> {
+        session.add_event_start(ActivityKind::Cache);
+        let result = match self {
+            Cache::Help => Help::Command(CommandName::Cache).run(session),
+            Cache::List => list(session),
+            Cache::Clean { all } => clean(session, all),
+            Cache::Verify => verify(),
+        };
+        session.add_event_end(ActivityKind::Cache, 0);
+        result
+    }
+}
+
+/// Describes an archive as `<tool> v<version>`.
+fn describe(archive: &Archive) -> String {
+    format!("{} v{}", archive.tool, archive.version)
+}
+
+fn list(session: &Session) -> Fallible<bool> {
+    let catalog = session.catalog()?;
+    let archives = cache::archives()?;
+
+    let mut total = 0;
+    for archive in &archives {
+        let mut flags = vec![];
+        if !archive.is_used(catalog) {
+            flags.push("unused");
+        }
+        if archive.partial {
+            flags.push("partial");
+        }
+        let flags = if flags.is_empty() {
+            String::new()
+        } else {
+            format!(" ({})", flags.join(", "))
+        };
+        println!("{: <18} {: >10}{}", describe(archive), human_size(archive.size), flags);
+        total += archive.size;
+    }
+
+    println!("{} archives, {} total", archives.len(), human_size(total));
+    Ok(true)
+}
+
+fn clean(session: &Session, all: bool) -> Fallible<bool> {
+    let catalog = session.catalog()?;
+
+    let mut freed = 0;
+    for archive in cache::archives()? {
+        if all || !archive.is_used(catalog) {
+            if archive.remove()? {
+                println!("removed {} ({})", describe(&archive), human_size(archive.size));
+                freed += archive.size;
+            } else {
+                println!("skipped {} (being installed)", describe(&archive));
+            }
+        }
+    }
+
+    println!("freed {}", human_size(freed));
+    Ok(true)
+}
+
+fn verify() -> Fallible<bool> {
+    let mut ok = true;
+    for archive in cache::archives()? {
+        match archive.verify()? {
+            Verified::Ok => {
+                println!("{}: ok", describe(&archive));
+            }
+            Verified::Mismatch { expected, actual } => {
+                println!(
+                    "{}: checksum mismatch (expected {}, got {})",
+                    describe(&archive),
+                    expected,
+                    actual
+                );
+                ok = false;
+            }
+            Verified::Incomplete => {
+                println!("{}: incomplete download", describe(&archive));
+            }
+            Verified::NoChecksum => {
+                println!("{}: no checksum available", describe(&archive));
+            }
+        }
+    }
+    Ok(ok)
+}
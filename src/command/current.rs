@@ -8,15 +8,23 @@ use command::{Command, CommandName, Help};
 
 #[derive(Debug, Deserialize)]
 pub(crate) struct Args {
+    arg_tool: Option<ToolName>,
     flag_local: bool,
     flag_global: bool,
 }
 
+/// The tools whose current versions can be displayed.
+#[derive(Debug, Deserialize, Clone, Copy)]
+pub(crate) enum ToolName {
+    Node,
+    Yarn,
+}
+
 pub(crate) enum Current {
     Help,
-    Local,
-    Global,
-    All,
+    Local(ToolName),
+    Global(ToolName),
+    All(Option<ToolName>),
 }
 
 impl Command for Current {
@@ -26,7 +34,11 @@ impl Command for Current {
 Display the currently activated toolchain
 
 Usage:
-    notion current [options]
+    notion current [options] [<tool>]
+
+Tools:
+    node           Display the Node version (the default)
+    yarn           Display the Yarn version
 
 Options:
     -h, --help     Display this message
@@ -41,16 +53,18 @@ Options:
     fn parse(
         _: Notion,
         Args {
+            arg_tool,
             flag_local,
             flag_global,
         }: Args,
     ) -> Fallible<Current> {
+        let tool = arg_tool.unwrap_or(ToolName::Node);
         Ok(if !flag_local && flag_global {
-            Current::Local
+            Current::Local(tool)
         } else if flag_local && !flag_global {
-            Current::Global
+            Current::Global(tool)
         } else {
-            Current::All
+            Current::All(arg_tool)
         })
     }
 
@@ -59,31 +73,21 @@ Options:
 
         let result = match self {
             Current::Help => Help::Command(CommandName::Current).run(session),
-            Current::Local => Ok(local(&session)?
-                .map(|version| {
+            Current::Local(tool) => Ok(local(&session, tool)?
+                .map(|(version, _)| {
                     println!("v{}", version);
                 })
                 .is_some()),
-            Current::Global => Ok(global(&session)?
+            Current::Global(tool) => Ok(global(&session, tool)?
                 .map(|version| {
                     println!("v{}", version);
                 })
                 .is_some()),
-            Current::All => {
-                let (local, global) = (local(&session)?, global(&session)?);
-                let global_active = local.is_none() && global.is_some();
-                let any = local.is_some() || global.is_some();
-                for version in local {
-                    println!("local: v{} (active)", version);
-                }
-                for version in global {
-                    println!(
-                        "global: v{}{}",
-                        version,
-                        if global_active { " (active)" } else { "" }
-                    );
-                }
-                Ok(any)
+            Current::All(Some(tool)) => all(&session, tool, ""),
+            Current::All(None) => {
+                let node = all(&session, ToolName::Node, "node ")?;
+                let yarn = all(&session, ToolName::Yarn, "yarn ")?;
+                Ok(node || yarn)
             }
         };
         session.add_event_end(ActivityKind::Current, 0);
@@ -91,7 +95,29 @@ Options:
     }
 }
 
-fn local(session: &Session) -> Fallible<Option<String>> {
+/// Displays the local and global versions of a tool, each line starting with
+/// the given prefix. Returns whether any version was found.
+fn all(session: &Session, tool: ToolName, prefix: &str) -> Fallible<bool> {
+    let (local, global) = (local(&session, tool)?, global(&session, tool)?);
+    let global_active = local.is_none() && global.is_some();
+    let any = local.is_some() || global.is_some();
+    for (version, source) in local {
+        println!("{}local: v{} (active, from {})", prefix, version, source);
+    }
+    for version in global {
+        println!(
+            "{}global: v{}{}",
+            prefix,
+            version,
+            if global_active { " (active)" } else { "" }
+        );
+    }
+    Ok(any)
+}
+
+/// Produces the project's version of a tool, along with the file that
+/// requested it.
+fn local(session: &Session, tool: ToolName) -> Fallible<Option<(String, String)>> {
     let project = session.project();
     let project = match project {
         Some(ref project) => project,
@@ -100,12 +126,29 @@ fn local(session: &Session) -> Fallible<Option<String>> {
         }
     };
 
-    let req = &project.manifest().node;
     let catalog = session.catalog()?;
-    Ok(catalog.node.resolve_local(&req).map(|v| v.to_string()))
+    Ok(match tool {
+        ToolName::Node => match (project.node(), project.node_source()) {
+            (Some(node), Some(source)) => catalog
+                .node
+                .resolve_local(node, session.config()?)
+                .map(|v| (v.to_string(), source.to_string())),
+            _ => None,
+        },
+        ToolName::Yarn => project
+            .manifest()
+            .yarn
+            .as_ref()
+            .and_then(|req| catalog.yarn.resolve_local(req))
+            .map(|v| (v.to_string(), String::from("package.json"))),
+    })
 }
 
-fn global(session: &Session) -> Fallible<Option<String>> {
+fn global(session: &Session, tool: ToolName) -> Fallible<Option<String>> {
     let catalog = session.catalog()?;
-    Ok(catalog.node.activated.clone().map(|v| v.to_string()))
+    let activated = match tool {
+        ToolName::Node => &catalog.node.activated,
+        ToolName::Yarn => &catalog.yarn.activated,
+    };
+    Ok(activated.clone().map(|v| v.to_string()))
 }
@@ -1,7 +1,8 @@
 use notion_core::session::{ActivityKind, Session};
 use notion_fail::Fallible;
 
-use command::{Command, CommandName, Current, Install, Uninstall, Use, Version};
+use command::{Cache, Command, CommandName, Current, Install, List, LsRemote, Prune, Shim,
+              Uninstall, Use, Version};
 use {CliParseError, Notion};
 
 #[derive(Debug, Deserialize)]
@@ -56,6 +57,11 @@ Options:
                 Help::Notion => Notion::USAGE,
                 Help::Command(CommandName::Use) => Use::USAGE,
                 Help::Command(CommandName::Current) => Current::USAGE,
+                Help::Command(CommandName::List) => List::USAGE,
+                Help::Command(CommandName::LsRemote) => LsRemote::USAGE,
+                Help::Command(CommandName::Cache) => Cache::USAGE,
+                Help::Command(CommandName::Prune) => Prune::USAGE,
+                Help::Command(CommandName::Shim) => Shim::USAGE,
                 Help::Command(CommandName::Help) => Help::USAGE,
                 Help::Command(CommandName::Version) => Version::USAGE,
                 Help::Command(CommandName::Install) => Install::USAGE,
@@ -1,20 +1,23 @@
-use semver::VersionReq;
+use std::path::PathBuf;
 
-use notion_core::serial::version::parse_requirements;
 use notion_core::session::{ActivityKind, Session};
 use notion_fail::Fallible;
+use semver::Version;
 
-use Notion;
-use command::{Command, CommandName, Help};
+use {CliParseError, Notion};
+use command::{Command, CommandName, Help, ToolSpec};
 
 #[derive(Debug, Deserialize)]
 pub(crate) struct Args {
-    arg_version: String,
+    arg_version: Option<String>,
+    flag_from_file: Option<String>,
+    flag_version: Option<String>,
 }
 
 pub(crate) enum Install {
     Help,
-    Default(VersionReq),
+    Default(ToolSpec),
+    FromFile(PathBuf, Option<Version>),
 }
 
 impl Command for Install {
@@ -23,30 +26,63 @@ impl Command for Install {
     const USAGE: &'static str = "
 Install a toolchain to the local machine
 
+A version can name its tool (e.g., `yarn@1.3`); otherwise it is a Node version.
+
 Usage:
     notion install <version>
+    notion install --from-file=<archive> [--version=<version>]
     notion install -h | --help
 
 Options:
-    -h, --help     Display this message
+    -h, --help               Display this message
+    --from-file=<archive>    Install Node from a downloaded archive file
+    --version=<version>      The Node version of the archive (by default, it is
+                             determined from the archive's contents)
 ";
 
     fn help() -> Self {
         Install::Help
     }
 
-    fn parse(_: Notion, Args { arg_version }: Args) -> Fallible<Self> {
-        let version = parse_requirements(&arg_version)?;
-        Ok(Install::Default(version))
+    fn parse(
+        _: Notion,
+        Args {
+            arg_version,
+            flag_from_file,
+            flag_version,
+        }: Args,
+    ) -> Fallible<Self> {
+        if let Some(archive) = flag_from_file {
+            let version = match flag_version {
+                Some(version) => Some(parse_version(&version)?),
+                None => None,
+            };
+            return Ok(Install::FromFile(PathBuf::from(archive), version));
+        }
+
+        match arg_version {
+            Some(version) => Ok(Install::Default(ToolSpec::parse(&version)?)),
+            None => Ok(Install::Help),
+        }
     }
 
     fn run(self, session: &mut Session) -> Fallible<bool> {
         session.add_event_start(ActivityKind::Install);
         let result = match self {
             Install::Help => Help::Command(CommandName::Install).run(session),
-            Install::Default(version) => {
+            Install::Default(ToolSpec::Node(version)) => {
                 session.install_node(&version)?;
 
+                Ok(true)
+            }
+            Install::Default(ToolSpec::Yarn(version)) => {
+                session.install_yarn(&version)?;
+
+                Ok(true)
+            }
+            Install::FromFile(archive, version) => {
+                session.install_node_from_file(&archive, version)?;
+
                 Ok(true)
             }
         };
@@ -54,3 +90,17 @@ Options:
         result
     }
 }
+
+/// Parses an exact Node version, with an optional leading `v` (e.g., `v10.1.0`).
+fn parse_version(src: &str) -> Fallible<Version> {
+    let trimmed = if src.starts_with('v') { &src[1..] } else { src };
+    match Version::parse(trimmed) {
+        Ok(version) => Ok(version),
+        Err(_) => {
+            throw!(CliParseError {
+                usage: None,
+                error: format!("invalid version '{}' (expected an exact version, e.g., 10.1.0)", src),
+            });
+        }
+    }
+}
@@ -0,0 +1,116 @@
+use serde_json;
+
+use notion_core::session::{ActivityKind, Session};
+use notion_core::style::human_size;
+use notion_fail::{Fallible, ResultExt};
+
+use Notion;
+use command::{Command, CommandName, Help};
+
+#[derive(Debug, Deserialize)]
+pub(crate) struct Args {
+    flag_format: Format,
+}
+
+#[derive(Debug, Deserialize, Clone, Copy)]
+pub(crate) enum Format {
+    Text,
+    Json,
+}
+
+pub(crate) enum List {
+    Help,
+    Default(Format),
+}
+
+/// A locally installed Node version, as reported by `notion list`.
+#[derive(Serialize)]
+struct Entry {
+    version: String,
+    global: bool,
+    local: bool,
+    size: Option<u64>,
+}
+
+impl Command for List {
+    type Args = Args;
+
+    const USAGE: &'static str = "
+List the locally installed toolchains
+
+Usage:
+    notion list [options]
+    notion list -h | --help
+
+Options:
+    -h, --help               Display this message
+    --format=<format>        Output format: 'text' or 'json' [default: text]
+";
+
+    fn help() -> Self {
+        List::Help
+    }
+
+    fn parse(_: Notion, Args { flag_format }: Args) -> Fallible<Self> {
+        Ok(List::Default(flag_format))
+    }
+
+    fn run(self, session: &mut Session) -> Fallible<bool> {
+        session.add_event_start(ActivityKind::List);
+        let result = match self {
+            List::Help => Help::Command(CommandName::List).run(session),
+            List::Default(format) => {
+                let entries = entries(&session)?;
+                match format {
+                    Format::Text => {
+                        for entry in &entries {
+                            let mut flags = vec![];
+                            if entry.local {
+                                flags.push("local");
+                            }
+                            if entry.global {
+                                flags.push("global");
+                            }
+                            let flags = if flags.is_empty() {
+                                String::new()
+                            } else {
+                                format!(" ({})", flags.join(", "))
+                            };
+                            let size = match entry.size {
+                                Some(size) => human_size(size),
+                                None => String::from("-"),
+                            };
+                            println!("v{: <12} {: >10}{}", entry.version, size, flags);
+                        }
+                    }
+                    Format::Json => {
+                        println!("{}", serde_json::to_string_pretty(&entries).unknown()?);
+                    }
+                }
+                Ok(true)
+            }
+        };
+        session.add_event_end(ActivityKind::List, 0);
+        result
+    }
+}
+
+fn entries(session: &Session) -> Fallible<Vec<Entry>> {
+    let catalog = session.catalog()?;
+    let config = session.config()?;
+    let local = session
+        .project()
+        .and_then(|project| project.node())
+        .and_then(|node| catalog.node.resolve_local(node, config));
+
+    let mut entries = Vec::new();
+    for version in catalog.node.versions.iter() {
+        entries.push(Entry {
+            version: version.to_string(),
+            global: catalog.node.activated.as_ref() == Some(version),
+            local: local.as_ref() == Some(version),
+            size: catalog.node.disk_usage(version)?,
+        });
+    }
+    Ok(entries)
+}
@@ -0,0 +1,77 @@
+use notion_core::serial::version::parse_requirements;
+use notion_core::session::{ActivityKind, Session};
+use notion_core::version::VersionSpec;
+use notion_fail::Fallible;
+
+use Notion;
+use command::{Command, CommandName, Help};
+
+#[derive(Debug, Deserialize)]
+pub(crate) struct Args {
+    arg_range: Option<String>,
+}
+
+pub(crate) enum LsRemote {
+    Help,
+    Default(Option<VersionSpec>),
+}
+
+impl Command for LsRemote {
+    type Args = Args;
+
+    const USAGE: &'static str = "
+List the Node versions available for installation
+
+Usage:
+    notion ls-remote [<range>]
+    notion ls-remote -h | --help
+
+Options:
+    -h, --help     Display this message
+";
+
+    fn help() -> Self {
+        LsRemote::Help
+    }
+
+    fn parse(_: Notion, Args { arg_range }: Args) -> Fallible<Self> {
+        Ok(LsRemote::Default(match arg_range {
+            Some(range) => Some(parse_requirements(&range)?),
+            None => None,
+        }))
+    }
+
+    fn run(self, session: &mut Session) -> Fallible<bool> {
+        session.add_event_start(ActivityKind::LsRemote);
+        let result = match self {
+            LsRemote::Help => Help::Command(CommandName::LsRemote).run(session),
+            LsRemote::Default(matching) => {
+                let catalog = session.catalog()?;
+                let index = catalog.node.ls_remote(session.config()?)?;
+                let versions = index
+                    .entries
+                    .iter()
+                    .filter(|&(version, data)| {
+                        matching
+                            .as_ref()
+                            .map_or(true, |spec| data.matches(version, spec))
+                    })
+                    .map(|(version, _)| version);
+
+                for version in versions {
+                    let status = if catalog.node.activated.as_ref() == Some(version) {
+                        " (active)"
+                    } else if catalog.node.contains(version) {
+                        " (installed)"
+                    } else {
+                        ""
+                    };
+                    println!("v{}{}", version, status);
+                }
+                Ok(true)
+            }
+        };
+        session.add_event_end(ActivityKind::LsRemote, 0);
+        result
+    }
+}
@@ -1,13 +1,23 @@
+mod cache;
 mod current;
 mod help;
 mod install;
+mod list;
+mod ls_remote;
+mod prune;
+mod shim;
 mod uninstall;
 mod use_;
 mod version;
 
+pub(crate) use self::cache::Cache;
 pub(crate) use self::current::Current;
 pub(crate) use self::help::Help;
 pub(crate) use self::install::Install;
+pub(crate) use self::list::List;
+pub(crate) use self::ls_remote::LsRemote;
+pub(crate) use self::prune::Prune;
+pub(crate) use self::shim::Shim;
 pub(crate) use self::uninstall::Uninstall;
 pub(crate) use self::use_::Use;
 pub(crate) use self::version::Version;
@@ -15,7 +25,9 @@ pub(crate) use self::version::Version;
 use docopt::Docopt;
 use serde::de::DeserializeOwned;
 
+use notion_core::serial::version::parse_requirements;
 use notion_core::session::Session;
+use notion_core::version::VersionSpec;
 use notion_fail::{FailExt, Fallible};
 
 use {CliParseError, DocoptExt, Notion};
@@ -30,6 +42,12 @@ pub(crate) enum CommandName {
     Uninstall,
     Use,
     Current,
+    List,
+    #[serde(rename = "ls-remote")]
+    LsRemote,
+    Cache,
+    Prune,
+    Shim,
     Help,
     Version,
 }
@@ -44,6 +62,11 @@ impl Display for CommandName {
                 CommandName::Uninstall => "uninstall",
                 CommandName::Use => "use",
                 CommandName::Current => "current",
+                CommandName::List => "list",
+                CommandName::LsRemote => "ls-remote",
+                CommandName::Cache => "cache",
+                CommandName::Prune => "prune",
+                CommandName::Shim => "shim",
                 CommandName::Help => "help",
                 CommandName::Version => "version",
             }
@@ -60,6 +83,11 @@ impl FromStr for CommandName {
             "uninstall" => CommandName::Uninstall,
             "use" => CommandName::Use,
             "current" => CommandName::Current,
+            "list" => CommandName::List,
+            "ls-remote" => CommandName::LsRemote,
+            "cache" => CommandName::Cache,
+            "prune" => CommandName::Prune,
+            "shim" => CommandName::Shim,
             "help" => CommandName::Help,
             "version" => CommandName::Version,
             _ => {
@@ -69,6 +97,36 @@ impl FromStr for CommandName {
     }
 }
 
+/// A tool version requested on the command line, in the form `<tool>@<version>`
+/// (e.g., `yarn@1.3`). A bare version (e.g., `8.9`) requests a version of Node.
+pub(crate) enum ToolSpec {
+    Node(VersionSpec),
+    Yarn(VersionSpec),
+}
+
+impl ToolSpec {
+    pub(crate) fn parse(src: &str) -> Fallible<Self> {
+        let index = match src.find('@') {
+            Some(index) => index,
+            None => {
+                return Ok(ToolSpec::Node(parse_requirements(src)?));
+            }
+        };
+
+        let version = parse_requirements(&src[index + 1..])?;
+        Ok(match &src[..index] {
+            "node" => ToolSpec::Node(version),
+            "yarn" => ToolSpec::Yarn(version),
+            tool => {
+                throw!(CliParseError {
+                    usage: None,
+                    error: format!("Unknown tool '{}' (expected 'node' or 'yarn')", tool),
+                });
+            }
+        })
+    }
+}
+
 /// A Notion command.
 pub(crate) trait Command: Sized {
     /// The intermediate type Docopt should deserialize the parsed command into.
@@ -112,3 +170,38 @@ pub(crate) trait Command: Sized {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn tool_spec_bare_version_is_node() {
+        match ToolSpec::parse("8.9").unwrap() {
+            ToolSpec::Node(spec) => assert_eq!(spec, parse_requirements("8.9").unwrap()),
+            ToolSpec::Yarn(_) => panic!("expected a Node version"),
+        }
+        match ToolSpec::parse("lts").unwrap() {
+            ToolSpec::Node(spec) => assert_eq!(spec, VersionSpec::Lts),
+            ToolSpec::Yarn(_) => panic!("expected a Node version"),
+        }
+    }
+
+    #[test]
+    fn tool_spec_with_tool() {
+        match ToolSpec::parse("node@latest").unwrap() {
+            ToolSpec::Node(spec) => assert_eq!(spec, VersionSpec::Latest),
+            ToolSpec::Yarn(_) => panic!("expected a Node version"),
+        }
+        match ToolSpec::parse("yarn@1.3").unwrap() {
+            ToolSpec::Yarn(spec) => assert_eq!(spec, parse_requirements("1.3").unwrap()),
+            ToolSpec::Node(_) => panic!("expected a Yarn version"),
+        }
+    }
+
+    #[test]
+    fn tool_spec_unknown_tool() {
+        assert!(ToolSpec::parse("npm@5").is_err());
+        assert!(ToolSpec::parse("yarn@not a version").is_err());
+    }
+}
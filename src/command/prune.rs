@@ -0,0 +1,101 @@
+use notion_core::session::{ActivityKind, Session};
+use notion_core::style::human_size;
+use notion_fail::Fallible;
+
+use Notion;
+use command::{Command, CommandName, Help};
+
+#[derive(Debug, Deserialize)]
+pub(crate) struct Args {
+    flag_dry_run: bool,
+    flag_keep: usize,
+}
+
+pub(crate) enum Prune {
+    Help,
+    Default { dry_run: bool, keep: usize },
+}
+
+impl Command for Prune {
+    type Args = Args;
+
+    const USAGE: &'static str = "
+Uninstall the Node versions that are no longer in use
+
+Usage:
+    notion prune [options]
+    notion prune -h | --help
+
+Options:
+    -h, --help               Display this message
+    --dry-run                List the versions to uninstall without uninstalling them
+    --keep=<n>               Keep the newest <n> versions of each major version [default: 0]
+
+A version is in use if it is the global version, the current project's
+version, or it has been launched in the last 30 days.
+";
+
+    fn help() -> Self {
+        Prune::Help
+    }
+
+    fn parse(
+        _: Notion,
+        Args {
+            flag_dry_run,
+            flag_keep,
+        }: Args,
+    ) -> Fallible<Self> {
+        Ok(Prune::Default {
+            dry_run: flag_dry_run,
+            keep: flag_keep,
+        })
+    }
+
+    fn run(self, session: &mut Session) -> Fallible<bool> {
+        session.add_event_start(ActivityKind::Prune);
+        let result = match self {
+            Prune::Help => Help::Command(CommandName::Prune).run(session),
+            Prune::Default { dry_run, keep } => prune(session, dry_run, keep),
+        };
+        session.add_event_end(ActivityKind::Prune, 0);
+        result
+    }
+}
+
+fn prune(session: &mut Session, dry_run: bool, keep: usize) -> Fallible<bool> {
+    let mut prunable = Vec::new();
+    {
+        let catalog = session.catalog()?;
+        let config = session.config()?;
+        let in_use: Vec<_> = session
+            .project()
+            .and_then(|project| project.node())
+            .and_then(|node| catalog.node.resolve_local(node, config))
+            .into_iter()
+            .collect();
+
+        for version in catalog.node.prunable(&in_use, keep)? {
+            let size = catalog.node.disk_usage(&version)?.unwrap_or(0);
+            prunable.push((version, size));
+        }
+    }
+
+    let mut freed = 0;
+    for (version, size) in prunable {
+        if dry_run {
+            println!("would uninstall v{} ({})", version, human_size(size));
+        } else {
+            session.catalog_mut()?.uninstall_node(&version)?;
+            println!("uninstalled v{} ({})", version, human_size(size));
+        }
+        freed += size;
+    }
+
+    if dry_run {
+        println!("would free {}", human_size(freed));
+    } else {
+        println!("freed {}", human_size(freed));
+    }
+    Ok(true)
+}
@@ -0,0 +1,108 @@
+use notion_core::session::{ActivityKind, Session};
+use notion_core::shim;
+use notion_fail::Fallible;
+
+use Notion;
+use command::{Command, CommandName, Help};
+
+#[derive(Debug, Deserialize)]
+pub(crate) struct Args {
+    cmd_add: bool,
+    cmd_list: bool,
+    cmd_remove: bool,
+    arg_name: Option<String>,
+    flag_script: bool,
+}
+
+pub(crate) enum Shim {
+    Help,
+    Add { name: String, script: bool },
+    List,
+    Remove(String),
+}
+
+impl Command for Shim {
+    type Args = Args;
+
+    const USAGE: &'static str = "
+Manage the shims that delegate to the executables of the Node version in use
+
+Usage:
+    notion shim add <name> [--script]
+    notion shim list
+    notion shim remove <name>
+    notion shim -h | --help
+
+Commands:
+    add            Create a shim for an executable
+    list           List the shims, flagging broken ones
+    remove         Remove a shim
+
+Options:
+    -h, --help     Display this message
+    --script       Launch the executable as a script (like npm) instead of a binary
+";
+
+    fn help() -> Self {
+        Shim::Help
+    }
+
+    fn parse(
+        _: Notion,
+        Args {
+            cmd_add,
+            cmd_list,
+            cmd_remove,
+            arg_name,
+            flag_script,
+        }: Args,
+    ) -> Fallible<Self> {
+        Ok(match (arg_name, cmd_add, cmd_list, cmd_remove) {
+            (Some(name), true, _, _) => Shim::Add {
+                name,
+                script: flag_script,
+            },
+            (_, _, true, _) => Shim::List,
+            (Some(name), _, _, true) => Shim::Remove(name),
+            _ => Shim::Help,
+        })
+    }
+
+    fn run(self, session: &mut Session) -> Fallible<bool> {
+        session.add_event_start(ActivityKind::Shim);
+        let result = match self {
+            Shim::Help => Help::Command(CommandName::Shim).run(session),
+            Shim::Add { name, script } => {
+                shim::create(&name, script)?;
+                Ok(true)
+            }
+            Shim::List => list(),
+            Shim::Remove(name) => {
+                shim::remove(&name)?;
+                Ok(true)
+            }
+        };
+        session.add_event_end(ActivityKind::Shim, 0);
+        result
+    }
+}
+
+fn list() -> Fallible<bool> {
+    let shims = shim::list()?;
+    for shim in &shims {
+        match shim.missing_target {
+            Some(ref target) => {
+                println!(
+                    "{: <20} {} (broken: {} is missing)",
+                    shim.name,
+                    shim.kind,
+                    target.display()
+                );
+            }
+            None => {
+                println!("{: <20} {}", shim.name, shim.kind);
+            }
+        }
+    }
+    Ok(!shims.iter().any(shim::Shim::is_broken))
+}
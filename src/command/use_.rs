@@ -2,16 +2,11 @@
 // With https://github.com/rust-lang/rfcs/blob/master/text/2151-raw-identifiers.md we
 // could consider something like `r#use` instead.
 
-use semver::VersionReq;
-
-use notion_core::serial::version::parse_requirements;
 use notion_core::session::{ActivityKind, Session};
 use notion_fail::Fallible;
 
 use Notion;
-use command::{Command, CommandName, Help};
-
-use std::process::exit;
+use command::{Command, CommandName, Help, ToolSpec};
 
 #[derive(Debug, Deserialize)]
 pub(crate) struct Args {
@@ -21,8 +16,8 @@ pub(crate) struct Args {
 
 pub(crate) enum Use {
     Help,
-    Global(VersionReq),
-    Local(VersionReq),
+    Global(ToolSpec),
+    Local(ToolSpec),
 }
 
 impl Command for Use {
@@ -31,6 +26,8 @@ impl Command for Use {
     const USAGE: &'static str = "
 Activate a particular toolchain version
 
+A version can name its tool (e.g., `yarn@1.3`); otherwise it is a Node version.
+
 Usage:
     notion use [options] <version>
     notion use -h | --help
@@ -51,7 +48,7 @@ Options:
             flag_global,
         }: Args,
     ) -> Fallible<Self> {
-        let requirements = parse_requirements(&arg_version)?;
+        let requirements = ToolSpec::parse(&arg_version)?;
         Ok(if flag_global {
             Use::Global(requirements)
         } else {
@@ -65,12 +62,17 @@ Options:
             Use::Help => {
                 Help::Command(CommandName::Use).run(session)?;
             }
-            Use::Global(requirements) => {
+            Use::Global(ToolSpec::Node(requirements)) => {
                 session.activate_node(&requirements)?;
             }
-            Use::Local(_) => {
-                println!("not yet implemented; in the meantime you can modify your package.json.");
-                exit(1);
+            Use::Global(ToolSpec::Yarn(requirements)) => {
+                session.activate_yarn(&requirements)?;
+            }
+            Use::Local(ToolSpec::Node(requirements)) => {
+                session.pin_node(&requirements)?;
+            }
+            Use::Local(ToolSpec::Yarn(requirements)) => {
+                session.pin_yarn(&requirements)?;
             }
         };
         session.add_event_end(ActivityKind::Use, 0);
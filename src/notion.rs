@@ -10,6 +10,7 @@ extern crate semver;
 extern crate serde;
 #[macro_use]
 extern crate serde_derive;
+extern crate serde_json;
 
 mod command;
 mod error;
@@ -23,7 +24,8 @@ use notion_core::session::{ActivityKind, Session};
 use notion_core::style::{display_error, display_unknown_error};
 use notion_fail::{FailExt, Fallible, NotionError};
 
-use command::{Command, CommandName, Current, Help, Install, Uninstall, Use, Version};
+use command::{Cache, Command, CommandName, Current, Help, Install, List, LsRemote, Prune,
+              Shim, Uninstall, Use, Version};
 use error::{CliParseError, DocoptExt, NotionErrorExt};
 
 pub const VERSION: &'static str = env!("CARGO_PKG_VERSION");
@@ -61,6 +63,11 @@ Some common notion commands are:
     uninstall      Uninstall a toolchain from the local machine
     use            Activate a particular toolchain version
     current        Display the currently activated toolchain version
+    list           List the locally installed toolchains
+    ls-remote      List the Node versions available for installation
+    cache          Inspect and clean the cache of downloaded archives
+    prune          Uninstall the Node versions that are no longer in use
+    shim           Create, list and remove shims
     help           Display this message
     version        Print version info and exit
 
@@ -171,6 +178,11 @@ See 'notion help <command>' for more information on a specific command.
             CommandName::Uninstall => Uninstall::go(self, session),
             CommandName::Use => Use::go(self, session),
             CommandName::Current => Current::go(self, session),
+            CommandName::List => List::go(self, session),
+            CommandName::LsRemote => LsRemote::go(self, session),
+            CommandName::Cache => Cache::go(self, session),
+            CommandName::Prune => Prune::go(self, session),
+            CommandName::Shim => Shim::go(self, session),
             CommandName::Help => Help::go(self, session),
             CommandName::Version => Version::go(self, session),
         }